@@ -0,0 +1,87 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The git revision holding the exercises as they were shipped, e.g. an
+// upstream tag. Without it, the root commit of the repository is used.
+const ORIGINAL_REF_VAR: &str = "RUSTLINGS_ORIGINAL_REF";
+
+// Embed the original exercise sources into the binary so that exercises
+// can be reset without relying on git. They are taken from a fixed revision
+// rather than from the working tree or the latest commits, which hold the
+// work of the student by the time the binary is rebuilt.
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed={ORIGINAL_REF_VAR}");
+
+    // Without git or a repository nothing is embedded, and resetting
+    // an exercise reports that its original source is not known
+    let sources = original_sources(&manifest_dir).unwrap_or_default();
+    let mut entries: Vec<String> = sources
+        .iter()
+        .map(|(key, source)| format!("    ({key:?}, {source:?}),\n"))
+        .collect();
+    entries.sort();
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("pristine.rs");
+    fs::write(
+        out,
+        format!(
+            "pub static PRISTINE_EXERCISES: &[(&str, &str)] = &[\n{}];\n",
+            entries.concat()
+        ),
+    )
+    .unwrap();
+}
+
+// The exercise sources of the original revision, keyed by their path
+// relative to the manifest directory, or None if git, the repository or the
+// revision is not available
+fn original_sources(manifest_dir: &Path) -> Option<Vec<(String, String)>> {
+    let git = |args: &[&str]| -> Option<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(manifest_dir)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    let commit = match env::var(ORIGINAL_REF_VAR) {
+        Ok(reference) => {
+            // A branch or tag may be moved, e.g. by fetching from upstream
+            let git_dir = manifest_dir.join(git(&["rev-parse", "--git-dir"])?.trim());
+            for file in ["refs", "packed-refs"] {
+                let path = git_dir.join(file);
+                if path.exists() {
+                    println!("cargo:rerun-if-changed={}", path.display());
+                }
+            }
+            let commit = git(&["rev-parse", "--verify", &format!("{reference}^{{commit}}")]);
+            if commit.is_none() {
+                println!("cargo:warning={ORIGINAL_REF_VAR} `{reference}` is not a commit");
+            }
+            commit?
+        }
+        // Commits are listed newest first, so the oldest root comes last
+        Err(_) => git(&["rev-list", "--max-parents=0", "HEAD"])?
+            .lines()
+            .last()?
+            .to_string(),
+    };
+    let commit = commit.trim();
+
+    // Paths are listed relative to the manifest directory
+    let files = git(&["ls-tree", "-r", "-z", "--name-only", commit, "--", "exercises"])?;
+    let sources: Vec<(String, String)> = files
+        .split('\0')
+        .filter(|path| path.ends_with(".rs") && !path.split('/').any(|part| part == "target"))
+        .filter_map(|path| Some((path.to_string(), git(&["show", &format!("{commit}:./{path}")])?)))
+        .collect();
+    (!sources.is_empty()).then_some(sources)
+}
@@ -0,0 +1,191 @@
+use crate::exercise::Exercise;
+use crate::ui::style;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+// The lines shown before and after the line of a frame
+const CONTEXT: usize = 1;
+const BACKTRACE_START: &str = "stack backtrace:";
+const FRAME_REGEX: &str = r"^\s*\d+: (.+)$";
+const LOCATION_REGEX: &str = r"^\s*at (.+):(\d+):(\d+)$";
+
+// A frame of the backtrace
+#[derive(Debug, PartialEq)]
+struct Frame {
+    function: String,
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+// Parse the frames of the backtrace starting at the given lines, up to the
+// note that ends it. Returns the frames and the number of lines they span.
+fn frames(lines: &[&str]) -> (Vec<Frame>, usize) {
+    let frame = Regex::new(FRAME_REGEX).unwrap();
+    let location = Regex::new(LOCATION_REGEX).unwrap();
+    let mut frames = Vec::new();
+    let mut function = None;
+    let mut consumed = 0;
+    for line in lines {
+        if let Some(captures) = frame.captures(line) {
+            function = Some(captures[1].to_string());
+        } else if let Some(captures) = location.captures(line) {
+            if let Some(function) = function.take() {
+                frames.push(Frame {
+                    function,
+                    file: captures[1].to_string(),
+                    line: captures[2].parse().unwrap_or_default(),
+                    column: captures[3].parse().unwrap_or_default(),
+                });
+            }
+        } else if line.starts_with("note: ") {
+            consumed += 1;
+            break;
+        } else {
+            break;
+        }
+        consumed += 1;
+    }
+    (frames, consumed)
+}
+
+// Whether the file of a frame is the one of the exercise. rustc reports the
+// path the way it was given to it, possibly with a leading `./`.
+fn is_exercise_file(file: &str, path: &Path) -> bool {
+    let file = Path::new(file);
+    let file = file.strip_prefix(".").unwrap_or(file);
+    file == path.strip_prefix(".").unwrap_or(path)
+}
+
+// The frame with the lines of the exercise around it, the line of the frame
+// marked
+fn render_frame(frame: &Frame, source: &[&str]) -> String {
+    let mut rendered = format!(
+        "  {} at {}:{}:{}",
+        style(&frame.function).bold(),
+        frame.file,
+        frame.line,
+        frame.column
+    );
+    let first = frame.line.saturating_sub(CONTEXT).max(1);
+    let last = (frame.line + CONTEXT).min(source.len());
+    let width = last.to_string().len();
+    for number in first..=last {
+        let line = source[number - 1];
+        if number == frame.line {
+            rendered.push_str(&format!("\n  {} {number:>width$} | {line}", style(">").red().bold()));
+        } else {
+            rendered.push_str(&format!("\n    {number:>width$} | {line}"));
+        }
+    }
+    rendered
+}
+
+// The output of the exercise with every backtrace in it reduced to the
+// frames in the exercise file. A backtrace without such frames is left out.
+pub fn render(stderr: &str, exercise: &Exercise) -> String {
+    if !stderr.contains(BACKTRACE_START) {
+        return stderr.to_string();
+    }
+    let source = fs::read_to_string(&exercise.path).unwrap_or_default();
+    filter(stderr, &exercise.path, &source.lines().collect::<Vec<_>>())
+}
+
+fn filter(stderr: &str, path: &Path, source: &[&str]) -> String {
+    let lines: Vec<&str> = stderr.lines().collect();
+    let mut rendered = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].trim_end() != BACKTRACE_START {
+            rendered.push(lines[i].to_string());
+            i += 1;
+            continue;
+        }
+        let (frames, consumed) = frames(&lines[i + 1..]);
+        let own: Vec<&Frame> = frames
+            .iter()
+            .filter(|frame| is_exercise_file(&frame.file, path) && (1..=source.len()).contains(&frame.line))
+            .collect();
+        if !own.is_empty() {
+            rendered.push(t!("run.backtrace"));
+            rendered.extend(own.iter().map(|frame| render_frame(frame, source)));
+        }
+        i += 1 + consumed;
+    }
+    let mut rendered = rendered.join("\n");
+    if stderr.ends_with('\n') {
+        rendered.push('\n');
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PATH: &str = "exercises/vecs/panic1.rs";
+    const SOURCE: &str = "fn main() {
+    let last = last(&[1, 2, 3]);
+}
+
+fn last(v: &[i32]) -> i32 {
+    v[5]
+}";
+    const STDERR: &str = "
+thread 'main' panicked at ./exercises/vecs/panic1.rs:6:5:
+index out of bounds: the len is 3 but the index is 5
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+             at /rustc/0123/library/std/src/panicking.rs:689:5
+   1: core::panicking::panic_bounds_check
+             at /rustc/0123/library/core/src/panicking.rs:280:5
+   2: panic1::last
+             at ./exercises/vecs/panic1.rs:6:5
+   3: panic1::main
+             at ./exercises/vecs/panic1.rs:2:16
+   4: core::ops::function::FnOnce::call_once
+             at /rustc/0123/library/core/src/ops/function.rs:250:5
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+";
+
+    #[test]
+    fn test_frames() {
+        let lines: Vec<&str> = STDERR.lines().skip(4).collect();
+        let (frames, consumed) = frames(&lines);
+        assert_eq!(frames.len(), 5);
+        assert_eq!(consumed, lines.len());
+        assert_eq!(
+            frames[2],
+            Frame {
+                function: "panic1::last".into(),
+                file: "./exercises/vecs/panic1.rs".into(),
+                line: 6,
+                column: 5,
+            }
+        );
+        assert!(is_exercise_file(&frames[2].file, Path::new(PATH)));
+        assert!(!is_exercise_file(&frames[0].file, Path::new(PATH)));
+    }
+
+    #[test]
+    fn test_filter_keeps_frames_of_the_exercise() {
+        let source: Vec<&str> = SOURCE.lines().collect();
+        let rendered = filter(STDERR, Path::new(PATH), &source);
+        let rendered = console::strip_ansi_codes(&rendered);
+        assert!(rendered.contains("index out of bounds: the len is 3 but the index is 5\n"));
+        assert!(rendered.contains("panic1::last at ./exercises/vecs/panic1.rs:6:5\n    5 | fn last"));
+        assert!(rendered.contains("  > 6 |     v[5]\n    7 | }"));
+        assert!(rendered.contains("panic1::main at ./exercises/vecs/panic1.rs:2:16"));
+        assert!(!rendered.contains("panicking.rs"));
+        assert!(!rendered.contains("RUST_BACKTRACE=full"));
+    }
+
+    #[test]
+    fn test_filter_leaves_out_foreign_backtraces() {
+        let rendered = filter(STDERR, Path::new("exercises/other.rs"), &[]);
+        assert!(rendered.contains("index out of bounds"));
+        assert!(!rendered.contains("stack backtrace:"));
+        assert!(!rendered.contains("panic1::last"));
+    }
+}
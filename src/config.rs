@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+use toml::value::{Table, Value};
+
+// The project configuration, next to info.toml
+const PROJECT_FILE: &str = "rustlings.toml";
+
+// Defaults for what can otherwise only be given on the command line or in
+// the environment. Every setting is optional, unset ones fall back to the
+// next layer: the project configuration overrides the user configuration.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    // Show the output of test exercises, like `--nocapture`
+    pub nocapture: Option<bool>,
+    // Show hints on success in watch mode, like `--success-hints`
+    pub success_hints: Option<bool>,
+    // Commit exercises as they start passing in watch mode, like `--auto-commit`
+    pub auto_commit: Option<bool>,
+    // Use emoji in messages, `false` is like setting NO_EMOJI
+    pub emoji: Option<bool>,
+    // Use colors in messages, detected from the terminal if unset
+    pub color: Option<bool>,
+    // Plain output for screen readers, like `--plain`
+    pub plain: Option<bool>,
+    // The language of messages, like `en` or `zh-CN`
+    pub locale: Option<String>,
+    // How many exercises `cicvverify` checks at the same time
+    pub jobs: Option<usize>,
+    // Seconds an exercise may run before it is stopped
+    pub timeout: Option<u64>,
+    // How `verify` reports its results
+    pub reporter: Option<Reporter>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Reporter {
+    // Progress and messages for people
+    Pretty,
+    // A line of JSON per exercise, for scripts
+    Json,
+}
+
+// Where a setting comes from
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Layer {
+    User,
+    Project,
+}
+
+impl Layer {
+    pub fn path(self) -> Option<PathBuf> {
+        match self {
+            Layer::User => env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home::home_dir().map(|home| home.join(".config")))
+                .map(|dir| dir.join("rustlings").join("config.toml")),
+            Layer::Project => Some(PathBuf::from(PROJECT_FILE)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Layer::User => "user",
+            Layer::Project => "project",
+        }
+    }
+}
+
+// The settings of a single layer, as written in its file
+fn read_table(layer: Layer) -> Result<Table, String> {
+    let path = match layer.path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(Table::new()),
+    };
+    let content =
+        fs::read_to_string(&path).map_err(|e| t!("common.read_failed", path = path.display(), error = e))?;
+    let table: Table =
+        toml::from_str(&content).map_err(|e| t!("common.parse_failed", path = path.display(), error = e))?;
+    // Check the settings now, so that mistakes are reported with the file they are in
+    parse(&table).map_err(|e| t!("config.invalid_setting", path = path.display(), error = e))?;
+    Ok(table)
+}
+
+fn parse(table: &Table) -> Result<Config, String> {
+    Config::deserialize(Value::Table(table.clone())).map_err(|e| e.to_string())
+}
+
+// The settings of both layers, the project overriding the user configuration
+fn merged_table() -> Result<Table, String> {
+    let mut table = read_table(Layer::User)?;
+    table.extend(read_table(Layer::Project)?);
+    Ok(table)
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+// Load the configuration of the user and of the project in the current directory
+pub fn load() -> Result<(), String> {
+    let config = parse(&merged_table()?)?;
+    let _ignored = CONFIG.set(config);
+    Ok(())
+}
+
+// The configuration loaded at startup, or the defaults if there is none
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+impl Config {
+    pub fn nocapture(&self) -> bool {
+        self.nocapture.unwrap_or(false)
+    }
+
+    pub fn success_hints(&self) -> bool {
+        self.success_hints.unwrap_or(false)
+    }
+
+    pub fn auto_commit(&self) -> bool {
+        self.auto_commit.unwrap_or(false)
+    }
+
+    pub fn plain(&self) -> bool {
+        self.plain.unwrap_or(false)
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs
+            .filter(|&jobs| jobs > 0)
+            .or_else(|| thread::available_parallelism().ok().map(|n| n.get()))
+            .unwrap_or(1)
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+
+    pub fn reporter(&self) -> Reporter {
+        self.reporter.unwrap_or(Reporter::Pretty)
+    }
+}
+
+// The keys of all settings, in the order they are listed
+const KEYS: &[&str] = &[
+    "nocapture",
+    "success-hints",
+    "auto-commit",
+    "emoji",
+    "color",
+    "plain",
+    "locale",
+    "jobs",
+    "timeout",
+    "reporter",
+];
+
+// Print the value of a setting, as merged from both layers
+pub fn print_value(key: &str) -> Result<(), String> {
+    check_key(key)?;
+    if let Some(value) = merged_table()?.get(key) {
+        println!("{}", display(value));
+    }
+    Ok(())
+}
+
+// Print every setting that is set, along with the layer it comes from
+pub fn print_all() -> Result<(), String> {
+    let user = read_table(Layer::User)?;
+    let project = read_table(Layer::Project)?;
+    for key in KEYS {
+        let (value, layer) = match (project.get(*key), user.get(*key)) {
+            (Some(value), _) => (value, Layer::Project),
+            (None, Some(value)) => (value, Layer::User),
+            (None, None) => continue,
+        };
+        println!("{key} = {} ({})", display(value), layer.name());
+    }
+    Ok(())
+}
+
+// Change a setting in the given layer, or remove it if there is no value
+pub fn set(layer: Layer, key: &str, value: Option<&str>) -> Result<PathBuf, String> {
+    check_key(key)?;
+    let path = layer
+        .path()
+        .ok_or_else(|| t!("config.no_home"))?;
+    let mut table = read_table(layer)?;
+    match value {
+        Some(value) => {
+            table.insert(key.to_string(), parse_value(value));
+        }
+        None => {
+            table.remove(key);
+        }
+    }
+    parse(&table).map_err(|e| t!("config.invalid_value", key = key, error = e))?;
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| t!("common.create_failed", path = dir.display(), error = e))?;
+    }
+    let content = toml::to_string(&table).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| t!("common.write_failed", path = path.display(), error = e))?;
+    Ok(path)
+}
+
+fn check_key(key: &str) -> Result<(), String> {
+    if KEYS.contains(&key) {
+        Ok(())
+    } else {
+        Err(t!("config.unknown_key", key = key, keys = KEYS.join(", ")))
+    }
+}
+
+// Values given on the command line are booleans or numbers if they look like one
+fn parse_value(value: &str) -> Value {
+    if let Ok(boolean) = value.parse() {
+        Value::Boolean(boolean)
+    } else if let Ok(integer) = value.parse() {
+        Value::Integer(integer)
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let mut table = Table::new();
+        table.insert("success-hints".into(), parse_value("true"));
+        table.insert("jobs".into(), parse_value("4"));
+        table.insert("reporter".into(), parse_value("json"));
+        let config = parse(&table).unwrap();
+        assert!(config.success_hints());
+        assert_eq!(config.jobs(), 4);
+        assert_eq!(config.reporter(), Reporter::Json);
+        assert!(!config.nocapture());
+
+        table.insert("jobs".into(), parse_value("many"));
+        assert!(parse(&table).is_err());
+        table.remove("jobs");
+        table.insert("colour".into(), parse_value("false"));
+        assert!(parse(&table).is_err());
+    }
+
+    #[test]
+    fn test_keys_match_settings() {
+        let config = Config {
+            nocapture: Some(true),
+            success_hints: Some(true),
+            auto_commit: Some(true),
+            emoji: Some(true),
+            color: Some(true),
+            plain: Some(true),
+            locale: Some("en".into()),
+            jobs: Some(1),
+            timeout: Some(1),
+            reporter: Some(Reporter::Pretty),
+        };
+        let table = match Value::try_from(config).unwrap() {
+            Value::Table(table) => table,
+            _ => unreachable!(),
+        };
+        let mut serialized: Vec<_> = table.keys().map(String::as_str).collect();
+        let mut keys = KEYS.to_vec();
+        serialized.sort_unstable();
+        keys.sort_unstable();
+        assert_eq!(serialized, keys);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::exercise::{Exercise, ExerciseList};
+use crate::progress::{Progress, Verified};
+use crate::watch::{changed_paths, is_info_path, start_watcher};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 7373;
+const INFO_FILE: &str = "info.toml";
+const PAGE: &str = include_str!("dashboard.html");
+// How often to check whether watch mode moved on or verified again
+const PROGRESS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// What the page shows, and the pages following along
+struct Dashboard {
+    exercises: Vec<Exercise>,
+    // The index of the exercise being worked on, None once all are done
+    current: Option<usize>,
+    // The outcome of the last verification in watch mode
+    verified: Option<Verified>,
+    listeners: Vec<Sender<String>>,
+}
+
+impl Dashboard {
+    fn state(&self) -> Value {
+        let mut topics: Vec<(String, usize, usize)> = Vec::new();
+        for exercise in &self.exercises {
+            let topic = exercise.topic();
+            let done = usize::from(exercise.looks_done());
+            match topics.iter_mut().find(|(name, _, _)| *name == topic) {
+                Some((_, topic_done, total)) => {
+                    *topic_done += done;
+                    *total += 1;
+                }
+                None => topics.push((topic, done, 1)),
+            }
+        }
+        let done = self.exercises.iter().filter(|e| e.looks_done()).count();
+        let current = self.current.map(|i| &self.exercises[i]);
+        // Until watch mode verified the exercise, the outcome of another one is not shown
+        let verification = self
+            .verified
+            .as_ref()
+            .filter(|verified| current.is_some_and(|exercise| exercise.name == verified.name))
+            .map(|verified| &verified.verification);
+        let current = current.map(|exercise| {
+            json!({
+                "name": exercise.name,
+                "title": exercise.title(),
+                "path": exercise.path,
+                "topic": exercise.topic(),
+                "passed": verification.is_some_and(|v| v.passed),
+                "output": verification.map_or("", |v| v.output.as_str()),
+                "hint": exercise.hint.get(),
+            })
+        });
+        json!({
+            "topics": topics
+                .iter()
+                .map(|(name, done, total)| json!({ "name": name, "done": done, "total": total }))
+                .collect::<Vec<_>>(),
+            "done": done,
+            "total": self.exercises.len(),
+            "current": current,
+            "verifying": current.is_some() && verification.is_none(),
+            "labels": {
+                "title": t!("dashboard.title"),
+                "progress": t!("dashboard.progress", done = done, total = self.exercises.len()),
+                "verifying": t!("dashboard.verifying"),
+                "passed": t!("dashboard.passed"),
+                "failed": t!("dashboard.failed"),
+                "hint": t!("dashboard.hint"),
+                "all_done": t!("dashboard.all_done"),
+            },
+        })
+    }
+
+    // Send the state to every page still open
+    fn publish(&mut self) {
+        let state = self.state().to_string();
+        self.listeners.retain(|listener| listener.send(state.clone()).is_ok());
+    }
+}
+
+pub fn dashboard(exercises: &[Exercise], port: u16) -> Result<(), String> {
+    let listener =
+        TcpListener::bind(("127.0.0.1", port)).map_err(|e| t!("dashboard.bind_failed", port = port, error = e))?;
+    let dashboard = Arc::new(Mutex::new(Dashboard {
+        exercises: exercises.to_vec(),
+        current: None,
+        verified: None,
+        listeners: Vec::new(),
+    }));
+    success!("{}", t!("dashboard.listening", url = format!("http://localhost:{port}/")));
+
+    let follower = Arc::clone(&dashboard);
+    thread::spawn(move || follow_changes(&follower));
+
+    for stream in listener.incoming().flatten() {
+        let dashboard = Arc::clone(&dashboard);
+        thread::spawn(move || {
+            let _ignored = respond(stream, &dashboard);
+        });
+    }
+    Ok(())
+}
+
+// Show what watch mode saves about the exercise it is working on, and the
+// progress again whenever a file changes. Verifying is left to watch mode.
+fn follow_changes(dashboard: &Mutex<Dashboard>) {
+    let (tx, rx) = channel();
+    let (_watcher, notify_error) = start_watcher(tx, None);
+    if let Some(e) = notify_error {
+        warn!("{}", t!("watch.polling_instead", error = e));
+    }
+    let mut progress = Progress::load();
+    refresh(dashboard, &progress);
+    loop {
+        let changed = match rx.recv_timeout(PROGRESS_CHECK_INTERVAL) {
+            Ok(event) => changed_paths(event, &rx),
+            // The state saved by watch mode is not among the files watched
+            Err(RecvTimeoutError::Timeout) => {
+                let saved = Progress::load();
+                if saved != progress {
+                    progress = saved;
+                    refresh(dashboard, &progress);
+                }
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if changed.iter().any(|path| is_info_path(path)) {
+            match ExerciseList::load(Path::new(INFO_FILE)) {
+                Ok(list) => dashboard.lock().unwrap().exercises = list.exercises,
+                Err(e) => warn!("{}", t!("watch.reload_failed", error = e)),
+            }
+        }
+        progress = Progress::load();
+        refresh(dashboard, &progress);
+    }
+}
+
+// The exercise watch mode is focused on, or else the first pending one that
+// was not skipped. None once all are done.
+fn focused(progress: &Progress, exercises: &[Exercise]) -> Option<usize> {
+    let next = progress.next_pending(exercises, 0)?;
+    Some(progress.current_index(exercises).unwrap_or(next))
+}
+
+// Show the exercise watch mode is focused on with its last verification
+fn refresh(dashboard: &Mutex<Dashboard>, progress: &Progress) {
+    let mut dashboard = dashboard.lock().unwrap();
+    dashboard.current = focused(progress, &dashboard.exercises);
+    dashboard.verified = progress.verified.clone();
+    dashboard.publish();
+}
+
+fn respond(stream: TcpStream, dashboard: &Mutex<Dashboard>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut host = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let mut stream = stream;
+    // Only pages served from this machine may read the exercises, not ones
+    // of other sites that have their name resolve to it
+    if !host.as_deref().is_some_and(is_local_host) {
+        return write_response(&mut stream, "403 Forbidden", "text/plain", "Forbidden");
+    }
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/")) => write_response(&mut stream, "200 OK", "text/html; charset=utf-8", PAGE),
+        (Some("GET"), Some("/state")) => {
+            let state = dashboard.lock().unwrap().state().to_string();
+            write_response(&mut stream, "200 OK", "application/json", &state)
+        }
+        (Some("GET"), Some("/events")) => {
+            let (tx, rx) = channel();
+            dashboard.lock().unwrap().listeners.push(tx);
+            send_events(&mut stream, &rx)
+        }
+        (Some("GET"), _) => write_response(&mut stream, "404 Not Found", "text/plain", "Not Found"),
+        _ => write_response(&mut stream, "405 Method Not Allowed", "text/plain", "Method Not Allowed"),
+    }
+}
+
+fn is_local_host(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    matches!(name, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+// Stream the states published until the page is closed
+fn send_events(stream: &mut TcpStream, states: &Receiver<String>) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\nConnection: keep-alive\r\n\r\n"
+    )?;
+    stream.flush()?;
+    for state in states {
+        write!(stream, "data: {state}\n\n")?;
+        stream.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use crate::verify::Verification;
+    use std::io::Read;
+
+    fn dashboard() -> Dashboard {
+        let exercises = ["finished_exercise", "pending_exercise", "pending_test_exercise"]
+            .map(|name| Exercise::for_test(name, format!("tests/fixture/state/{name}.rs"), Mode::Compile));
+        Dashboard {
+            exercises: exercises.to_vec(),
+            current: None,
+            verified: None,
+            listeners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_focused_follows_watch_mode() {
+        let exercises = dashboard().exercises;
+        let mut progress = Progress::default();
+        assert_eq!(focused(&progress, &exercises), Some(1));
+        progress.current = Some("pending_test_exercise".into());
+        assert_eq!(focused(&progress, &exercises), Some(2));
+        progress.current = Some("removed_exercise".into());
+        assert_eq!(focused(&progress, &exercises), Some(1));
+        assert_eq!(focused(&progress, &exercises[..1]), None);
+    }
+
+    #[test]
+    fn test_state() {
+        let mut dashboard = dashboard();
+        dashboard.current = Some(2);
+        dashboard.verified = Some(Verified {
+            name: "pending_test_exercise".into(),
+            verification: Verification {
+                passed: false,
+                output: "not yet".into(),
+            },
+        });
+        let state = dashboard.state();
+        assert_eq!(state["done"], 1);
+        assert_eq!(state["total"], 3);
+        assert_eq!(state["topics"][0], json!({ "name": "state", "done": 1, "total": 3 }));
+        assert_eq!(state["current"]["name"], "pending_test_exercise");
+        assert_eq!(state["current"]["passed"], false);
+        assert_eq!(state["current"]["output"], "not yet");
+        assert_eq!(state["verifying"], false);
+
+        // Watch mode moved on and did not verify the next exercise yet
+        dashboard.current = Some(1);
+        let state = dashboard.state();
+        assert_eq!(state["current"]["name"], "pending_exercise");
+        assert_eq!(state["current"]["output"], "");
+        assert_eq!(state["verifying"], true);
+    }
+
+    #[test]
+    fn test_events_follow_published_states() {
+        let dashboard = Arc::new(Mutex::new(dashboard()));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let server = Arc::clone(&dashboard);
+        thread::spawn(move || respond(stream, &server));
+
+        client
+            .write_all(b"GET /events HTTP/1.1\r\nHost: localhost:7373\r\n\r\n")
+            .unwrap();
+        while dashboard.lock().unwrap().listeners.is_empty() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        {
+            let mut dashboard = dashboard.lock().unwrap();
+            dashboard.current = Some(1);
+            dashboard.publish();
+        }
+
+        let mut received = String::new();
+        let mut buffer = [0; 4096];
+        while !received.ends_with("}\n\n") {
+            let read = client.read(&mut buffer).unwrap();
+            assert!(read > 0);
+            received.push_str(&String::from_utf8_lossy(&buffer[..read]));
+        }
+        assert!(received.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/event-stream"));
+        let data = received.split("data: ").nth(1).unwrap().trim();
+        let state: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(state["current"]["name"], "pending_exercise");
+    }
+
+    #[test]
+    fn test_only_local_hosts() {
+        assert!(is_local_host("localhost:7373"));
+        assert!(is_local_host("127.0.0.1:7373"));
+        assert!(is_local_host("[::1]:7373"));
+        assert!(is_local_host("localhost"));
+        assert!(!is_local_host("evil.example:7373"));
+        assert!(!is_local_host("localhost.evil.example"));
+    }
+}
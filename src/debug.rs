@@ -0,0 +1,141 @@
+use crate::exercise::{Exercise, Mode};
+use crate::ui::Spinner;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const DEBUG_DIR: &str = ".rustlings/debug";
+
+// The binary of the exercise built for debugging
+fn binary_path(exercise: &Exercise) -> PathBuf {
+    Path::new(DEBUG_DIR).join(format!("{}{}", exercise.name, env::consts::EXE_SUFFIX))
+}
+
+// The command lines that debug the binary with the given arguments, in the
+// order the debuggers are tried: lldb is the one of macOS, gdb elsewhere
+fn command_lines(binary: &Path, args: &[String]) -> Vec<Vec<String>> {
+    let binary = binary.display().to_string();
+    let gdb = ["rust-gdb", "--args", &binary]
+        .iter()
+        .map(ToString::to_string)
+        .chain(args.iter().cloned())
+        .collect();
+    let mut lldb: Vec<String> = vec!["rust-lldb".to_string(), binary];
+    if !args.is_empty() {
+        lldb.push("--".to_string());
+        lldb.extend(args.iter().cloned());
+    }
+    if cfg!(target_os = "macos") {
+        vec![lldb, gdb]
+    } else {
+        vec![gdb, lldb]
+    }
+}
+
+// Whether the program can be found in PATH
+fn is_installed(program: &str) -> bool {
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|dir| dir.join(format!("{program}{}", env::consts::EXE_SUFFIX)).is_file())
+    })
+}
+
+// The command line the way it would be typed into a shell
+fn display(command_line: &[String]) -> String {
+    command_line
+        .iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || "'\"$\\*?".contains(c)) {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Build the exercise for debugging and start the first debugger available,
+// or print how to start one when there is none or `print_only` is set
+pub fn debug(exercise: &Exercise, test_filter: Option<&str>, print_only: bool) -> Result<(), ()> {
+    if let Mode::BuildScript = exercise.mode {
+        warn!("{}", t!("debug.unsupported", exercise = exercise));
+        return Err(());
+    }
+    let mut args = Vec::new();
+    match (exercise.mode, test_filter) {
+        // One test at a time, with its output, is what can be followed
+        (Mode::Test, filter) => {
+            args.extend(["--test-threads=1".to_string(), "--nocapture".to_string()]);
+            args.extend(filter.map(ToString::to_string));
+        }
+        (_, Some(_)) => warn!("{}", t!("run.no_tests", exercise = exercise)),
+        (_, None) => {}
+    }
+
+    let binary = binary_path(exercise);
+    if let Err(e) = fs::create_dir_all(DEBUG_DIR) {
+        warn!("{}", t!("common.create_failed", path = DEBUG_DIR, error = e));
+        return Err(());
+    }
+    let progress_bar = Spinner::new(t!("verify.compiling", exercise = exercise));
+    let compilation = exercise.compile_for_debugging(&binary);
+    progress_bar.finish_and_clear();
+    if let Err(output) = compilation {
+        warn!("{}", t!("run.compile_failed", exercise = exercise));
+        println!("{}", output.stderr);
+        return Err(());
+    }
+    success!("{}", t!("debug.built", exercise = exercise, path = binary.display()));
+
+    let command_lines = command_lines(&binary, &args);
+    // The wrappers come with Rust, the debuggers themselves may not be installed
+    let available = command_lines
+        .iter()
+        .find(|line| is_installed(&line[0]) && is_installed(line[0].trim_start_matches("rust-")));
+    match available {
+        Some(command_line) if !print_only => {
+            println!("{}", t!("debug.launching", command = display(command_line)));
+            match Command::new(&command_line[0]).args(&command_line[1..]).status() {
+                Ok(status) if status.success() => Ok(()),
+                Ok(_) => Err(()),
+                Err(e) => {
+                    warn!("{}", t!("debug.launch_failed", debugger = command_line[0], error = e));
+                    Err(())
+                }
+            }
+        }
+        _ => {
+            if available.is_none() {
+                println!("{}", t!("debug.no_debugger"));
+            }
+            for command_line in &command_lines {
+                println!("  {}", display(command_line));
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_command_lines() {
+        let args = vec!["--test-threads=1".to_string(), "sorts empty".to_string()];
+        let lines = command_lines(Path::new(".rustlings/debug/sort1"), &args);
+        let gdb = lines.iter().find(|line| line[0] == "rust-gdb").unwrap();
+        assert_eq!(
+            display(gdb),
+            "rust-gdb --args .rustlings/debug/sort1 --test-threads=1 'sorts empty'"
+        );
+        let lldb = lines.iter().find(|line| line[0] == "rust-lldb").unwrap();
+        assert_eq!(
+            display(lldb),
+            "rust-lldb .rustlings/debug/sort1 -- --test-threads=1 'sorts empty'"
+        );
+        let lines = command_lines(Path::new("intro2"), &[]);
+        assert!(lines.iter().any(|line| display(line) == "rust-lldb intro2"));
+    }
+}
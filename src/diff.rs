@@ -0,0 +1,90 @@
+use crate::exercise::Exercise;
+use crate::pristine;
+use crate::ui::style;
+use similar::{ChangeTag, TextDiff};
+use std::fs;
+
+// Print a colored unified diff between the original source of the exercise
+// and the current file
+pub fn diff(exercise: &Exercise) -> Result<(), ()> {
+    let (original, current) = sources(exercise)?;
+    let text_diff = TextDiff::from_lines(original, current.as_str());
+    if text_diff.ratio() == 1.0 {
+        println!("{}", t!("diff.unchanged", exercise = exercise));
+        return Ok(());
+    }
+
+    let path = exercise.path.display();
+    println!("{}", style(format!("--- a/{path} ({})", t!("diff.original"))).bold());
+    println!("{}", style(format!("+++ b/{path}")).bold());
+    for hunk in text_diff.unified_diff().iter_hunks() {
+        println!("{}", style(hunk.header()).cyan());
+        for change in hunk.iter_changes() {
+            let line = format!("{}{}", change.tag(), change.value().trim_end_matches('\n'));
+            match change.tag() {
+                ChangeTag::Delete => println!("{}", style(line).red()),
+                ChangeTag::Insert => println!("{}", style(line).green()),
+                ChangeTag::Equal => println!("{line}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+// Print how many lines were added and removed in every changed exercise
+pub fn diff_stat<'a>(exercises: impl IntoIterator<Item = &'a Exercise>) {
+    let mut changed = 0;
+    let (mut total_insertions, mut total_deletions) = (0, 0);
+    for exercise in exercises {
+        // An exercise without a known original is warned about and left out
+        let Ok((original, current)) = sources(exercise) else {
+            continue;
+        };
+        let text_diff = TextDiff::from_lines(original, current.as_str());
+        let (insertions, deletions) =
+            text_diff
+                .iter_all_changes()
+                .fold((0, 0), |(insertions, deletions), change| match change.tag() {
+                    ChangeTag::Insert => (insertions + 1, deletions),
+                    ChangeTag::Delete => (insertions, deletions + 1),
+                    ChangeTag::Equal => (insertions, deletions),
+                });
+        if insertions + deletions == 0 {
+            continue;
+        }
+
+        changed += 1;
+        total_insertions += insertions;
+        total_deletions += deletions;
+        println!(
+            " {:<46} | {:>4} {}{}",
+            exercise.path.display(),
+            insertions + deletions,
+            style("+".repeat(insertions.min(20))).green(),
+            style("-".repeat(deletions.min(20))).red()
+        );
+    }
+    println!(
+        " {}",
+        t!(
+            "diff.summary",
+            changed = changed,
+            insertions = total_insertions,
+            deletions = total_deletions
+        )
+    );
+}
+
+// The original and the current source of the exercise
+fn sources(exercise: &Exercise) -> Result<(&'static str, String), ()> {
+    let original = match pristine::original(&exercise.path) {
+        Some(original) => original,
+        None => {
+            warn!("{}", t!("run.no_original", exercise = exercise));
+            return Err(());
+        }
+    };
+    // A deleted exercise shows up as everything being removed
+    let current = fs::read_to_string(&exercise.path).unwrap_or_default();
+    Ok((original, current))
+}
@@ -0,0 +1,236 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+// The label tasks written by rustlings are recognized by when setting up again
+const TASK_PREFIX: &str = "rustlings: ";
+// The first line of files that rustlings owns entirely
+const GENERATED_HEADER: &str = "-- Generated by `rustlings editor-setup nvim`";
+
+// Compiler errors and warnings, with the location on a following line
+const DIAGNOSTIC_REGEX: &str = r"^(error|warning)(?:\[\w+\])?: (.*)$";
+const LOCATION_REGEX: &str = r"^\s*--> (.*):(\d+):(\d+)$";
+// A failing test, `thread 'tests::it_works' panicked at exercises/x.rs:12:5:`
+const PANIC_REGEX: &str = r"^thread '(.*)' panicked at (.*):(\d+):(\d+):?$";
+
+// The same for the errorformat of Vim, following the one of rust.vim
+const ERRORFORMAT: &str = "%-G,%-Gerror: aborting %.%#,%-Gerror: could not compile %.%#,\
+%Eerror[E%n]: %m,%Eerror: %m,%Wwarning: %m,%C %#--> %f:%l:%c,%Z,\
+%Ethread '%m' panicked at %f:%l:%c:,%-G%.%#";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Editor {
+    VsCode,
+    Helix,
+    Nvim,
+}
+
+const EDITORS: &[(&str, Editor)] = &[
+    ("vscode", Editor::VsCode),
+    ("helix", Editor::Helix),
+    ("nvim", Editor::Nvim),
+];
+
+impl FromStr for Editor {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Editor, String> {
+        EDITORS
+            .iter()
+            .find(|(editor, _)| editor.eq_ignore_ascii_case(name))
+            .map(|(_, editor)| *editor)
+            .ok_or_else(|| {
+                let editors: Vec<&str> = EDITORS.iter().map(|(name, _)| *name).collect();
+                t!("editor.unknown", editor = name, editors = editors.join(", "))
+            })
+    }
+}
+
+// Write the configuration for the editor into the current directory
+pub fn setup(editor: Editor) -> Result<(), String> {
+    let (path, content, usage) = match editor {
+        Editor::VsCode => (".vscode/tasks.json", vscode_tasks()?, t!("editor.vscode_usage")),
+        Editor::Helix => (".helix/config.toml", helix_config()?, t!("editor.helix_usage")),
+        Editor::Nvim => (".nvim.lua", nvim_config()?, t!("editor.nvim_usage")),
+    };
+    let path = Path::new(path);
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| t!("common.create_failed", path = dir.display(), error = e))?;
+    }
+    fs::write(path, content).map_err(|e| t!("common.write_failed", path = path.display(), error = e))?;
+    success!("{}", t!("editor.written", path = path.display()));
+    println!("{usage}");
+    Ok(())
+}
+
+fn read_existing(path: &str) -> Result<Option<String>, String> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(path)
+        .map(Some)
+        .map_err(|e| t!("common.read_failed", path = path, error = e))
+}
+
+fn problem_matcher() -> Value {
+    json!({
+        "owner": "rustlings",
+        "fileLocation": ["relative", "${workspaceFolder}"],
+        "pattern": [
+            { "regexp": DIAGNOSTIC_REGEX, "severity": 1, "message": 2 },
+            { "regexp": LOCATION_REGEX, "file": 1, "line": 2, "column": 3 }
+        ]
+    })
+}
+
+fn panic_matcher() -> Value {
+    json!({
+        "owner": "rustlings",
+        "fileLocation": ["relative", "${workspaceFolder}"],
+        "severity": "error",
+        "pattern": { "regexp": PANIC_REGEX, "message": 1, "file": 2, "line": 3, "column": 4 }
+    })
+}
+
+fn task(label: &str, args: &[&str], is_default: bool) -> Value {
+    let mut task = json!({
+        "label": format!("{TASK_PREFIX}{label}"),
+        "type": "process",
+        "command": "rustlings",
+        "args": args,
+        "options": { "cwd": "${workspaceFolder}" },
+        "presentation": { "reveal": "always", "clear": true },
+        "problemMatcher": [problem_matcher(), panic_matcher()],
+    });
+    if is_default {
+        task["group"] = json!({ "kind": "build", "isDefault": true });
+    }
+    task
+}
+
+// The tasks of .vscode/tasks.json. Tasks of the user are kept, the ones of
+// rustlings are replaced.
+fn vscode_tasks() -> Result<String, String> {
+    let path = ".vscode/tasks.json";
+    let mut tasks = match read_existing(path)? {
+        Some(content) => serde_json::from_str(&content)
+            .map_err(|e| t!("editor.not_json", path = path, error = e))?,
+        None => json!({ "version": "2.0.0" }),
+    };
+    let Some(object) = tasks.as_object_mut() else {
+        return Err(t!("editor.not_json", path = path, error = "not an object"));
+    };
+    let mut list: Vec<Value> = match object.remove("tasks") {
+        Some(Value::Array(list)) => list,
+        _ => Vec::new(),
+    };
+    list.retain(|task| {
+        !task["label"]
+            .as_str()
+            .is_some_and(|label| label.starts_with(TASK_PREFIX))
+    });
+    list.push(task("run current exercise", &["--plain", "run", "${file}"], true));
+    list.push(task("hint for current exercise", &["--plain", "hint", "${file}"], false));
+    list.push(task("verify", &["--plain", "verify"], false));
+    object.insert("tasks".to_string(), Value::Array(list));
+    let mut content = serde_json::to_string_pretty(&tasks).map_err(|e| e.to_string())?;
+    content.push('\n');
+    Ok(content)
+}
+
+// The key bindings of .helix/config.toml, merged into the existing ones.
+// Helix has no problem matchers, its diagnostics come from rust-analyzer.
+fn helix_config() -> Result<String, String> {
+    let path = ".helix/config.toml";
+    let mut config: toml::Value = match read_existing(path)? {
+        Some(content) => toml::from_str(&content).map_err(|e| t!("common.parse_failed", path = path, error = e))?,
+        None => toml::Value::Table(toml::value::Table::new()),
+    };
+    let bindings = [
+        ("r", ":sh rustlings --plain run %{buffer_name}"),
+        ("h", ":sh rustlings --plain hint %{buffer_name}"),
+    ];
+    let mut table = &mut config;
+    for key in ["keys", "normal", "space", "x"] {
+        let toml::Value::Table(parent) = table else {
+            return Err(t!("editor.not_table", path = path, key = key));
+        };
+        table = parent
+            .entry(key.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+    }
+    let toml::Value::Table(rustlings) = table else {
+        return Err(t!("editor.not_table", path = path, key = "x"));
+    };
+    for (key, command) in bindings {
+        rustlings.insert(key.to_string(), toml::Value::String(command.to_string()));
+    }
+    toml::to_string_pretty(&config).map_err(|e| e.to_string())
+}
+
+// A local Neovim configuration, read when 'exrc' is set, with commands that
+// fill the quickfix list through the errorformat
+fn nvim_config() -> Result<String, String> {
+    let path = ".nvim.lua";
+    if let Some(content) = read_existing(path)? {
+        if !content.starts_with(GENERATED_HEADER) {
+            return Err(t!("editor.not_generated", path = path));
+        }
+    }
+    Ok(format!(
+        r#"{GENERATED_HEADER}, changes are lost when it runs again.
+local errorformat = [[{ERRORFORMAT}]]
+
+local function rustlings(args)
+  vim.cmd("silent! write")
+  vim.opt_local.makeprg = "rustlings --plain " .. args
+  vim.opt_local.errorformat = errorformat
+  vim.cmd("make!")
+  vim.cmd("cwindow")
+end
+
+vim.api.nvim_create_user_command("RustlingsRun", function()
+  rustlings("run " .. vim.fn.shellescape(vim.fn.expand("%")))
+end, {{ desc = "Run the exercise in the current buffer" }})
+
+vim.api.nvim_create_user_command("RustlingsHint", function()
+  vim.cmd("!rustlings --plain hint " .. vim.fn.shellescape(vim.fn.expand("%")))
+end, {{ desc = "Show the hint for the exercise in the current buffer" }})
+
+vim.api.nvim_create_user_command("RustlingsVerify", function()
+  rustlings("verify")
+end, {{ desc = "Verify all exercises" }})
+"#
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_matchers_find_locations() {
+        let diagnostic = Regex::new(DIAGNOSTIC_REGEX).unwrap();
+        let location = Regex::new(LOCATION_REGEX).unwrap();
+        let panic = Regex::new(PANIC_REGEX).unwrap();
+
+        let error = diagnostic.captures("error[E0425]: cannot find value `x` in this scope").unwrap();
+        assert_eq!(&error[1], "error");
+        assert_eq!(&error[2], "cannot find value `x` in this scope");
+        let at = location.captures(" --> exercises/variables/variables1.rs:11:5").unwrap();
+        assert_eq!((&at[1], &at[2], &at[3]), ("exercises/variables/variables1.rs", "11", "5"));
+        let failure = panic
+            .captures("thread 'tests::is_true' panicked at exercises/tests/tests1.rs:17:9:")
+            .unwrap();
+        assert_eq!((&failure[2], &failure[3]), ("exercises/tests/tests1.rs", "17"));
+    }
+
+    #[test]
+    fn test_editor_names() {
+        assert_eq!("vscode".parse(), Ok(Editor::VsCode));
+        assert_eq!("NVIM".parse(), Ok(Editor::Nvim));
+        assert!("emacs".parse::<Editor>().is_err());
+    }
+}
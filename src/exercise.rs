@@ -1,16 +1,27 @@
+use crate::backtrace;
+use crate::config;
+use crate::i18n::Localized;
+use crate::model::{self, Model, MODEL_CASES, MODEL_TEST_FILTER, MODEL_TIMEOUT};
+use crate::prefetch::{self, Prefetched};
+use crate::ui;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::BTreeMap;
 use std::env;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::{self, Child, Command, ExitStatus, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
-const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
-const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
+const DEFAULT_EDITION: &str = "2021";
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
+// Line numbers for the backtraces of panics, see `backtrace`
+const LINE_TABLES_ARGS: [&str; 2] = ["-C", "debuginfo=1"];
 const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
 const BUILD_SCRIPT_CARGO_TOML_PATH: &str = "./exercises/tests/Cargo.toml";
 
@@ -25,8 +36,14 @@ fn temp_file() -> String {
     format!("./temp_{}_{thread_id}", process::id())
 }
 
+// The file name of the reference model harness, next to the temporary binary
+#[inline]
+fn model_temp_file() -> String {
+    format!("{}_model", temp_file())
+}
+
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
@@ -44,9 +61,18 @@ pub struct ExerciseList {
     pub exercises: Vec<Exercise>,
 }
 
+impl ExerciseList {
+    // Read and parse the list of exercises from the given file
+    pub fn load(path: &Path) -> Result<ExerciseList, String> {
+        let toml_str =
+            fs::read_to_string(path).map_err(|e| t!("common.read_failed", path = path.display(), error = e))?;
+        toml::from_str(&toml_str).map_err(|e| t!("common.parse_failed", path = path.display(), error = e))
+    }
+}
+
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Clone, Debug)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
@@ -54,8 +80,28 @@ pub struct Exercise {
     pub path: PathBuf,
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
-    // The hint text associated with the exercise
-    pub hint: String,
+    // The hint text associated with the exercise, in one or several languages
+    pub hint: Localized,
+    // A short title and a description of what the exercise is about, if any
+    #[serde(default)]
+    pub title: Option<Localized>,
+    #[serde(default)]
+    pub description: Option<Localized>,
+    // The Rust edition, 2021 unless given
+    #[serde(default)]
+    pub edition: Option<String>,
+    // Options passed to rustc with `--cfg`, like `feature="pass"`
+    #[serde(default)]
+    pub cfg: Vec<String>,
+    // Environment variables set while compiling, to be read with `env!`.
+    // For exercises built with cargo, the configuration and variables their
+    // build script sets are given here for rust-analyzer instead.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    // The reference model the exercise is additionally checked against
+    // with random inputs, if any
+    #[serde(default)]
+    pub model: Option<Model>,
 }
 
 // An enum to track of the state of an Exercise.
@@ -82,16 +128,42 @@ pub struct ContextLine {
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
+    // The result of running it, if that already happened in the background
+    prefetched: Cell<Option<Result<ExerciseOutput, ExerciseOutput>>>,
     _handle: FileHandle,
 }
 
 impl<'a> CompiledExercise<'a> {
     // Run the compiled exercise
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        self.exercise.run()
+        self.run_with(&RunOptions::default())
+    }
+
+    // Run the compiled exercise the way the options ask for. Only a plain
+    // run can use what ran in the background.
+    pub fn run_with(&self, options: &RunOptions) -> Result<ExerciseOutput, ExerciseOutput> {
+        if *options == RunOptions::default() {
+            if let Some(result) = self.prefetched.take() {
+                return result;
+            }
+        }
+        self.exercise.run(options)
     }
 }
 
+// How `rustlings run` runs an exercise, beyond what verifying does
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct RunOptions {
+    // Arguments for the program, or for the test harness of test exercises
+    pub args: Vec<String>,
+    // Only run the tests whose names contain this
+    pub test_filter: Option<String>,
+    // Connect the program to the terminal, so that it can read from stdin
+    // and its output shows up as it is written. When stdin is a terminal the
+    // timeout doesn't apply, since the program may be waiting for input.
+    pub interactive: bool,
+}
+
 // A representation of an already executed binary
 #[derive(Debug)]
 pub struct ExerciseOutput {
@@ -110,30 +182,49 @@ impl Drop for FileHandle {
 }
 
 impl Exercise {
+    // Compile the exercise, unless that already happened in the background
     pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+        match prefetch::take(self) {
+            Some(Prefetched::CompileError(output)) => Err(output),
+            Some(Prefetched::Ran(result)) => Ok(CompiledExercise {
+                exercise: self,
+                prefetched: Cell::new(Some(result)),
+                _handle: FileHandle,
+            }),
+            None => self.compile_now(),
+        }
+    }
+
+    pub fn compile_now(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
         let cmd = match self.mode {
             Mode::Compile => Command::new("rustc")
                 .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
+                .args(LINE_TABLES_ARGS)
+                .args(ui::rustc_color_args())
+                .args(self.rustc_args())
+                .envs(&self.env)
                 .output(),
             Mode::Test => Command::new("rustc")
                 .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
+                .args(ui::rustc_color_args())
+                .args(self.rustc_args())
+                .envs(&self.env)
                 .output(),
             Mode::Clippy => {
                 let cargo_toml = format!(
                     r#"[package]
 name = "{}"
 version = "0.0.1"
-edition = "2021"
+edition = "{}"
 [[bin]]
 name = "{}"
 path = "{}.rs""#,
-                    self.name, self.name, self.name
+                    self.name,
+                    self.edition(),
+                    self.name,
+                    self.name
                 );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
+                let cargo_toml_error_msg = if !ui::emoji() {
                     "Failed to write Clippy Cargo.toml file."
                 } else {
                     "Failed to write 📎 Clippy 📎 Cargo.toml file."
@@ -145,8 +236,10 @@ path = "{}.rs""#,
                 // clippy to reflect the same failure while compiling later.
                 Command::new("rustc")
                     .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(RUSTC_EDITION_ARGS)
+                    .args(LINE_TABLES_ARGS)
+                    .args(ui::rustc_color_args())
+                    .args(self.rustc_args())
+                    .envs(&self.env)
                     .output()
                     .expect("Failed to compile!");
                 // Due to an issue with Clippy, a cargo clean is required to catch all lints.
@@ -155,12 +248,12 @@ path = "{}.rs""#,
                 // https://github.com/rust-lang/rust-clippy/issues/3837
                 Command::new("cargo")
                     .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
+                    .args(ui::rustc_color_args())
                     .output()
                     .expect("Failed to run 'cargo clean'");
                 Command::new("cargo")
                     .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
+                    .args(ui::rustc_color_args())
                     .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
                     .output()
             },
@@ -169,13 +262,16 @@ path = "{}.rs""#,
                     r#"[package]
 name = "{}"
 version = "0.0.1"
-edition = "2021"
+edition = "{}"
 [[bin]]
 name = "{}"
 path = "{}.rs""#,
-                    self.name, self.name, self.name
+                    self.name,
+                    self.edition(),
+                    self.name,
+                    self.name
                 );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
+                let cargo_toml_error_msg = if !ui::emoji() {
                     "Failed to write Clippy Cargo.toml file."
                 } else {
                     "Failed to write 📎 Clippy 📎 Cargo.toml file."
@@ -192,6 +288,7 @@ path = "{}.rs""#,
         if cmd.status.success() {
             Ok(CompiledExercise {
                 exercise: self,
+                prefetched: Cell::new(None),
                 _handle: FileHandle,
             })
         } else {
@@ -203,27 +300,121 @@ path = "{}.rs""#,
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
+    fn run(&self, options: &RunOptions) -> Result<ExerciseOutput, ExerciseOutput> {
+        let mut command = Command::new(temp_file());
+        match self.mode {
+            Mode::Test => {
+                command.arg("--show-output").args(&options.test_filter);
+            }
             Mode::BuildScript => return Ok(ExerciseOutput {
                 stdout: "".to_string(),
                 stderr: "".to_string(),
             }),
-            _ => "",
+            // Panics are shown with where they happened in the exercise,
+            // unless backtraces were asked for some other way
+            Mode::Compile | Mode::Clippy => {
+                if env::var_os("RUST_BACKTRACE").is_none() {
+                    command.env("RUST_BACKTRACE", "1");
+                }
+            }
         };
-        println!("pa={}", temp_file());
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
+        command.args(&options.args);
+        let timeout = config::get().timeout();
+        if options.interactive {
+            let timeout = timeout.filter(|_| !io::stdin().is_terminal());
+            // stderr still goes through rustlings, for the backtraces
+            let (status, timed_out, stderr) = command
+                .stderr(Stdio::piped())
+                .spawn()
+                .and_then(|mut child| {
+                    let reader = read_to_end(child.stderr.take());
+                    let (status, timed_out) = wait_with_timeout(child, timeout)?;
+                    Ok((status, timed_out, reader.join().unwrap_or_default()))
+                })
+                .expect("Failed to run 'run' command");
+            let mut output = ExerciseOutput {
+                stdout: String::new(),
+                stderr: backtrace::render(&String::from_utf8_lossy(&stderr), self),
+            };
+            if timed_out {
+                let seconds = timeout.unwrap_or_default().as_secs();
+                output
+                .stderr
+                .push_str(&format!("\n{}\n", t!("run.timed_out", exercise = self, seconds = seconds)));
+            }
+            return if status.success() && !timed_out { Ok(output) } else { Err(output) };
+        }
+        let (cmd, timed_out) = output_with_timeout(&mut command, timeout)
             .expect("Failed to run 'run' command");
 
-        let output = ExerciseOutput {
+        let mut output = ExerciseOutput {
+            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
+            stderr: backtrace::render(&String::from_utf8_lossy(&cmd.stderr), self),
+        };
+
+        if timed_out {
+            let seconds = timeout.unwrap_or_default().as_secs();
+            output.stderr.push_str(&format!(
+                "\n{}\n",
+                t!("run.timed_out", exercise = self, seconds = seconds)
+            ));
+            Err(output)
+        } else if cmd.status.success() {
+            Ok(output)
+        } else {
+            Err(output)
+        }
+    }
+
+    // Compile the exercise together with the property checks of its
+    // reference model and run them against freshly seeded random inputs
+    pub fn check_model(&self, model: Model) -> Result<ExerciseOutput, ExerciseOutput> {
+        let source = fs::read_to_string(&self.path).expect("We were unable to read the exercise file!");
+        let harness_binary = model_temp_file();
+        let harness_source = format!("{harness_binary}.rs");
+        fs::write(
+            &harness_source,
+            model::harness(&source, model, model::seed(), MODEL_CASES),
+        )
+        .expect("Failed to write the reference model harness.");
+
+        let compilation = Command::new("rustc")
+            .args(["--test", &harness_source, "-o", &harness_binary])
+            .args(ui::rustc_color_args())
+            .args(self.rustc_args())
+            .envs(&self.env)
+            .output()
+            .expect("Failed to run 'compile' command.");
+        let _ignored = remove_file(&harness_source);
+        if !compilation.status.success() {
+            return Err(ExerciseOutput {
+                stdout: String::from_utf8_lossy(&compilation.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&compilation.stderr).to_string(),
+            });
+        }
+
+        // A loop that never ends on some random input must not hang verify,
+        // so the checks are stopped even when no timeout is configured
+        let timeout = config::get().timeout().unwrap_or(MODEL_TIMEOUT);
+        let (cmd, timed_out) = output_with_timeout(
+            Command::new(&harness_binary).args([MODEL_TEST_FILTER, "--quiet"]),
+            Some(timeout),
+        )
+        .expect("Failed to run 'run' command");
+        let _ignored = remove_file(&harness_binary);
+
+        let mut output = ExerciseOutput {
             stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
             stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
         };
 
-        if cmd.status.success() {
+        if timed_out {
+            output.stderr.push_str(&format!(
+                "\n{}\n",
+                t!("run.timed_out", exercise = self, seconds = timeout.as_secs())
+            ));
+            Err(output)
+        } else if cmd.status.success() {
             Ok(output)
         } else {
             Err(output)
@@ -271,14 +462,91 @@ path = "{}.rs""#,
         State::Pending(context)
     }
 
+    // Compile the exercise, or its test harness, with debug info and without
+    // optimizations into the given binary, for running it in a debugger
+    pub fn compile_for_debugging(&self, binary: &Path) -> Result<(), ExerciseOutput> {
+        let mut command = Command::new("rustc");
+        if let Mode::Test = self.mode {
+            command.arg("--test");
+        }
+        let cmd = command
+            .arg(&self.path)
+            .arg("-o")
+            .arg(binary)
+            .args(["-g", "-C", "opt-level=0"])
+            .args(ui::rustc_color_args())
+            .args(self.rustc_args())
+            .envs(&self.env)
+            .output()
+            .expect("Failed to run 'compile' command.");
+        if cmd.status.success() {
+            Ok(())
+        } else {
+            Err(ExerciseOutput {
+                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            })
+        }
+    }
+
+    // An exercise with nothing but a name, a path and a mode, for tests
+    #[cfg(test)]
+    pub fn for_test(name: &str, path: impl Into<PathBuf>, mode: Mode) -> Exercise {
+        Exercise {
+            name: name.to_string(),
+            path: path.into(),
+            mode,
+            hint: Localized::default(),
+            title: None,
+            description: None,
+            edition: None,
+            cfg: Vec::new(),
+            env: BTreeMap::new(),
+            model: None,
+        }
+    }
+
+    pub fn edition(&self) -> &str {
+        self.edition.as_deref().unwrap_or(DEFAULT_EDITION)
+    }
+
+    // The edition and configuration options for rustc
+    fn rustc_args(&self) -> Vec<String> {
+        let mut args = vec!["--edition".to_string(), self.edition().to_string()];
+        for cfg in &self.cfg {
+            args.extend(["--cfg".to_string(), cfg.clone()]);
+        }
+        args
+    }
+
+    // The title of the exercise in the language in use, or else its name
+    pub fn title(&self) -> &str {
+        self.title.as_ref().map_or(&self.name, Localized::get)
+    }
+
+    // The topic of the exercise, which is the directory it lives in.
+    // Quizzes live directly in `exercises/`, so their topic is the file name
+    // without its number.
+    pub fn topic(&self) -> String {
+        match self.path.parent().and_then(|p| p.file_name()) {
+            Some(dir) if dir != "exercises" => dir.to_string_lossy().to_string(),
+            _ => self
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().trim_end_matches(|c: char| c.is_ascii_digit()).to_string())
+                .unwrap_or_default(),
+        }
+    }
+
     // Check that the exercise looks to be solved using self.state()
     // This is not the best way to check since
     // the user can just remove the "I AM NOT DONE" string from the file
     // without actually having solved anything.
     // The only other way to truly check this would to compile and run
     // the exercise; which would be both costly and counterintuitive
+    // A missing exercise file is never done.
     pub fn looks_done(&self) -> bool {
-        self.state() == State::Done
+        self.path.exists() && self.state() == State::Done
     }
 }
 
@@ -288,6 +556,60 @@ impl Display for Exercise {
     }
 }
 
+// Run the command to completion like `Command::output` does, but stop it once
+// it runs for longer than the timeout. Returns whether it was stopped.
+fn output_with_timeout(command: &mut Command, timeout: Option<Duration>) -> io::Result<(Output, bool)> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return command.output().map(|output| (output, false)),
+    };
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // The pipes are drained while waiting, so that a chatty child can't block on them
+    let stdout = read_to_end(child.stdout.take());
+    let stderr = read_to_end(child.stderr.take());
+
+    let (status, timed_out) = wait_with_timeout(child, Some(timeout))?;
+    let output = Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    };
+    Ok((output, timed_out))
+}
+
+// Wait for the child to exit, killing it once the timeout passes. Returns
+// whether it timed out.
+fn wait_with_timeout(mut child: Child, timeout: Option<Duration>) -> io::Result<(ExitStatus, bool)> {
+    let Some(timeout) = timeout else {
+        return child.wait().map(|status| (status, false));
+    };
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok((status, false));
+        }
+        if start.elapsed() >= timeout {
+            let _ignored = child.kill();
+            return child.wait().map(|status| (status, true));
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn read_to_end(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut content = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ignored = pipe.read_to_end(&mut content);
+        }
+        content
+    })
+}
+
 #[inline]
 fn clean() {
     let _ignored = remove_file(&temp_file());
@@ -301,12 +623,7 @@ mod test {
     #[test]
     fn test_clean() {
         File::create(&temp_file()).unwrap();
-        let exercise = Exercise {
-            name: String::from("example"),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::from(""),
-        };
+        let exercise = Exercise::for_test("example", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
         let compiled = exercise.compile().unwrap();
         drop(compiled);
         assert!(!Path::new(&temp_file()).exists());
@@ -314,12 +631,7 @@ mod test {
 
     #[test]
     fn test_pending_state() {
-        let exercise = Exercise {
-            name: "pending_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = Exercise::for_test("pending_exercise", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
 
         let state = exercise.state();
         let expected = vec![
@@ -355,24 +667,22 @@ mod test {
 
     #[test]
     fn test_finished_exercise() {
-        let exercise = Exercise {
-            name: "finished_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = Exercise::for_test("finished_exercise", "tests/fixture/state/finished_exercise.rs", Mode::Compile);
 
         assert_eq!(exercise.state(), State::Done);
     }
 
+    #[test]
+    fn test_topic() {
+        let exercise = |path: &str| Exercise::for_test("", path, Mode::Compile);
+
+        assert_eq!(exercise("exercises/if/if1.rs").topic(), "if");
+        assert_eq!(exercise("exercises/quiz2.rs").topic(), "quiz");
+    }
+
     #[test]
     fn test_exercise_with_output() {
-        let exercise = Exercise {
-            name: "exercise_with_output".into(),
-            path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
-            mode: Mode::Test,
-            hint: String::new(),
-        };
+        let exercise = Exercise::for_test("exercise_with_output", "tests/fixture/success/testSuccess.rs", Mode::Test);
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
@@ -0,0 +1,176 @@
+use crate::exercise::Exercise;
+use crate::ui::style;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Snapshots are stored once per content in `objects/`,
+// with one log of snapshots per exercise next to it
+const HISTORY_DIR: &str = ".rustlings/history";
+
+// The result of the verification a snapshot was taken for
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Passed,
+    Failed,
+    // Taken right before an older snapshot was restored
+    Unverified,
+}
+
+// A single snapshot in the history of an exercise
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Snapshot {
+    // Seconds since the unix epoch
+    pub time: u64,
+    // The content hash of the exercise file
+    pub hash: String,
+    pub outcome: Outcome,
+}
+
+// A stable 64 bit FNV-1a hash of the given content, as hex
+pub fn content_hash(content: &[u8]) -> String {
+    let hash = content.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+fn object_path(hash: &str) -> PathBuf {
+    Path::new(HISTORY_DIR).join("objects").join(hash)
+}
+
+fn log_path(exercise: &Exercise) -> PathBuf {
+    Path::new(HISTORY_DIR).join(format!("{}.json", exercise.name))
+}
+
+// All snapshots of the exercise, oldest first
+pub fn snapshots(exercise: &Exercise) -> Vec<Snapshot> {
+    fs::read_to_string(log_path(exercise))
+        .ok()
+        .and_then(|log| serde_json::from_str(&log).ok())
+        .unwrap_or_default()
+}
+
+// Take a snapshot of the current exercise file.
+// Nothing is recorded if neither the file nor the outcome changed since the last snapshot.
+pub fn record(exercise: &Exercise, outcome: Outcome) -> io::Result<()> {
+    let content = fs::read(&exercise.path)?;
+    let hash = content_hash(&content);
+
+    let mut snapshots = snapshots(exercise);
+    if let Some(last) = snapshots.last() {
+        if last.hash == hash && last.outcome == outcome {
+            return Ok(());
+        }
+    }
+
+    let object = object_path(&hash);
+    if !object.exists() {
+        fs::create_dir_all(object.parent().unwrap())?;
+        fs::write(&object, &content)?;
+    }
+
+    snapshots.push(Snapshot {
+        time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        hash,
+        outcome,
+    });
+    fs::write(log_path(exercise), serde_json::to_vec_pretty(&snapshots)?)
+}
+
+// Print the snapshots of the exercise, numbered for `--restore`
+pub fn list(exercise: &Exercise) {
+    let snapshots = snapshots(exercise);
+    if snapshots.is_empty() {
+        println!("{}", t!("history.none", exercise = exercise));
+        return;
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    println!(
+        "{:>4}  {:<16}  {:<10}  {:>5}  {}",
+        "#",
+        t!("history.snapshot"),
+        t!("history.outcome"),
+        t!("history.lines"),
+        t!("history.when")
+    );
+    for (number, snapshot) in snapshots.iter().enumerate() {
+        let lines = fs::read_to_string(object_path(&snapshot.hash))
+            .map(|content| content.lines().count().to_string())
+            .unwrap_or_else(|_| "?".to_string());
+        let outcome = match snapshot.outcome {
+            Outcome::Passed => style(format!("{:<10}", t!("history.passed"))).green(),
+            Outcome::Failed => style(format!("{:<10}", t!("history.failed"))).red(),
+            Outcome::Unverified => style(format!("{:<10}", t!("history.unverified"))).dim(),
+        };
+        println!(
+            "{:>4}  {:<16}  {outcome}  {lines:>5}  {}",
+            number + 1,
+            snapshot.hash,
+            age(now.saturating_sub(snapshot.time))
+        );
+    }
+}
+
+// Restore the snapshot with the given number as shown by `list`.
+// The current file is snapshotted first so that it can be restored as well.
+pub fn restore(exercise: &Exercise, number: usize) -> Result<(), ()> {
+    let snapshots = snapshots(exercise);
+    let snapshot = match number.checked_sub(1).and_then(|i| snapshots.get(i)) {
+        Some(snapshot) => snapshot,
+        None => {
+            warn!("{}", t!("history.no_such_snapshot", exercise = exercise));
+            return Err(());
+        }
+    };
+    let content = match fs::read(object_path(&snapshot.hash)) {
+        Ok(content) => content,
+        Err(e) => {
+            warn!("{}", t!("history.read_failed", error = e));
+            return Err(());
+        }
+    };
+
+    if exercise.path.exists() {
+        if let Err(e) = record(exercise, Outcome::Unverified) {
+            warn!("{}", t!("history.snapshot_failed", error = e));
+            return Err(());
+        }
+    }
+    if let Err(e) = fs::write(&exercise.path, content) {
+        warn!("{}", t!("history.restore_failed", error = e));
+        return Err(());
+    }
+    success!("{}", t!("history.restored", number = number));
+    Ok(())
+}
+
+fn age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => t!("history.just_now"),
+        60..=3599 => t!("history.minutes_ago", count = seconds / 60),
+        3600..=86399 => t!("history.hours_ago", count = seconds / 3600),
+        _ => t!("history.days_ago", count = seconds / 86400),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+        assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+    }
+}
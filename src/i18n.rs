@@ -0,0 +1,260 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use toml::value::{Table, Value};
+
+// Translate a message. Values for its placeholders are given by name:
+// `t!("run.reset", exercise = exercise)`
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::message($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::message(
+            $key,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+
+// The languages there are catalogs for, the first being the one every
+// other falls back to
+pub const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.toml")),
+    ("zh-CN", include_str!("../locales/zh-CN.toml")),
+];
+
+// The index of the language in use in LOCALES
+static LOCALE: AtomicUsize = AtomicUsize::new(0);
+
+// Choose the language given on the command line, else the configured one,
+// else the one of the environment. Returns an error naming the supported
+// languages if an explicitly chosen one is not among them.
+pub fn init(explicit: Option<&str>, configured: Option<&str>) -> Result<(), String> {
+    let chosen = explicit.or(configured);
+    let index = match chosen {
+        Some(locale) => find(locale).ok_or_else(|| unknown(locale)),
+        None => Ok(from_env().unwrap_or(0)),
+    };
+    LOCALE.store(*index.as_ref().unwrap_or(&0), Ordering::Relaxed);
+    index.map(|_| ())
+}
+
+// The supported language for a name like `zh_CN`, or an error naming the
+// supported languages
+pub fn resolve(name: &str) -> Result<&'static str, String> {
+    find(name).map(|index| LOCALES[index].0).ok_or_else(|| unknown(name))
+}
+
+fn unknown(locale: &str) -> String {
+    let supported: Vec<&str> = LOCALES.iter().map(|(name, _)| *name).collect();
+    t!("main.unknown_language", locale = locale, locales = supported.join(", "))
+}
+
+// The language in use
+pub fn locale() -> &'static str {
+    LOCALES[LOCALE.load(Ordering::Relaxed)].0
+}
+
+// The catalog for a language name like `zh-CN`, `zh_CN.UTF-8` or `en_US`:
+// an exact match, or else one for the same language in another region
+fn find(name: &str) -> Option<usize> {
+    let name = name.split('.').next().unwrap_or_default().replace('_', "-");
+    let language = |locale: &str| locale.split('-').next().unwrap_or_default().to_lowercase();
+    LOCALES
+        .iter()
+        .position(|(locale, _)| locale.eq_ignore_ascii_case(&name))
+        .or_else(|| {
+            LOCALES
+                .iter()
+                .position(|(locale, _)| language(locale) == language(&name))
+        })
+}
+
+// The language of the environment, as the C library would pick it
+fn from_env() -> Option<usize> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| find(&value))
+}
+
+// The messages of each catalog by `section.key`
+fn catalogs() -> &'static [HashMap<String, String>] {
+    static CATALOGS: OnceLock<Vec<HashMap<String, String>>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        LOCALES
+            .iter()
+            .map(|(name, content)| {
+                let table: Table = toml::from_str(content)
+                    .unwrap_or_else(|e| panic!("The {name} catalog is invalid: {e}"));
+                let mut messages = HashMap::new();
+                flatten("", &table, &mut messages);
+                messages
+            })
+            .collect()
+    })
+}
+
+fn flatten(prefix: &str, table: &Table, messages: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Value::Table(table) => flatten(&key, table, messages),
+            Value::String(message) => {
+                messages.insert(key, message.clone());
+            }
+            _ => panic!("The message {key} is not a string"),
+        }
+    }
+}
+
+// The message for the key in the language in use, falling back to English,
+// with its placeholders filled in
+pub fn message(key: &str, values: &[(&str, &dyn Display)]) -> String {
+    let catalogs = catalogs();
+    let template = catalogs[LOCALE.load(Ordering::Relaxed)]
+        .get(key)
+        .or_else(|| catalogs[0].get(key))
+        .map(String::as_str)
+        .unwrap_or(key);
+    let mut message = template.to_string();
+    for (name, value) in values {
+        message = message.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    message
+}
+
+// A text of info.toml, like a hint, given either as a single string or in
+// several languages: `hint.en = "..."` and `hint.zh-CN = "..."`
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Localized {
+    Text(String),
+    ByLocale(BTreeMap<String, String>),
+}
+
+impl Default for Localized {
+    fn default() -> Localized {
+        Localized::Text(String::new())
+    }
+}
+
+impl Localized {
+    // The text in the language in use, else in English, else in any language
+    pub fn get(&self) -> &str {
+        match self {
+            Localized::Text(text) => text,
+            Localized::ByLocale(texts) => texts
+                .get(locale())
+                .or_else(|| texts.get(LOCALES[0].0))
+                .or_else(|| texts.values().next())
+                .map_or("", String::as_str),
+        }
+    }
+}
+
+impl Display for Localized {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.get())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::fs;
+
+    fn placeholders(message: &str) -> BTreeSet<&str> {
+        message
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}'))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[test]
+    fn test_catalogs_have_the_same_messages() {
+        let catalogs = catalogs();
+        let english = &catalogs[0];
+        for ((name, _), catalog) in LOCALES.iter().zip(catalogs).skip(1) {
+            for (key, message) in english {
+                let translation = catalog
+                    .get(key)
+                    .unwrap_or_else(|| panic!("The {name} catalog has no message {key}"));
+                assert_eq!(
+                    placeholders(message),
+                    placeholders(translation),
+                    "The {name} message {key} has other placeholders"
+                );
+            }
+            for key in catalog.keys() {
+                assert!(english.contains_key(key), "The {name} message {key} is not in en");
+            }
+        }
+    }
+
+    #[test]
+    fn test_used_messages_exist() {
+        let english = &catalogs()[0];
+        for entry in fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/src")).unwrap() {
+            let path = entry.unwrap().path();
+            let source = fs::read_to_string(&path).unwrap();
+            for (start, _) in source.match_indices("t!(") {
+                // Not the end of another macro, like `format!(`
+                let is_word = |c: char| c.is_alphanumeric() || c == '_';
+                if source[..start].ends_with(is_word) {
+                    continue;
+                }
+                let Some(usage) = source[start + 3..].trim_start().strip_prefix('"') else {
+                    continue;
+                };
+                let key = usage.split('"').next().unwrap();
+                let is_key = |c: char| c.is_ascii_lowercase() || "._-".contains(c);
+                if key.contains('.') && key.chars().all(is_key) {
+                    assert!(english.contains_key(key), "{} uses unknown message {key}", path.display());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_localized_falls_back_to_english() {
+        #[derive(Deserialize)]
+        struct Texts {
+            plain: Localized,
+            english: Localized,
+            chinese: Localized,
+        }
+        let texts: Texts = toml::from_str(
+            "plain = \"Hint\"\nenglish.en = \"Hint\"\nenglish.zh-CN = \"提示\"\nchinese.zh-CN = \"提示\"\n",
+        )
+        .unwrap();
+        assert_eq!(texts.plain.get(), "Hint");
+        // The tests run in English
+        assert_eq!(texts.english.get(), "Hint");
+        assert_eq!(texts.chinese.get(), "提示");
+    }
+
+    #[test]
+    fn test_locale_names() {
+        assert_eq!(find("zh_CN.UTF-8"), Some(1));
+        assert_eq!(find("zh-TW"), Some(1));
+        assert_eq!(find("en_US.UTF-8"), Some(0));
+        assert_eq!(find("C"), None);
+        assert_eq!(
+            message("main.no_exercise_found", &[("name", &"intro9")]),
+            "No exercise found for 'intro9'!"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use crate::i18n::LOCALES;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EXERCISES_DIR: &str = "exercises";
+const CATALOG_DIR: &str = "locales/exercises";
+// The language exercises are written in
+const SOURCE_LOCALE: &str = "en";
+const MARKER_REGEX: &str = r"^(\s*)//\s*i18n(?:\(([A-Za-z-]+)\))?:\s*(\S+)\s*$";
+const I_AM_NOT_DONE_REGEX: &str = r"^\s*///?\s*I\s+AM\s+NOT\s+DONE";
+
+// A comment block of an exercise with a translation key
+#[derive(Debug, PartialEq)]
+struct Block {
+    key: String,
+    // The language the block is written in
+    locale: String,
+    // The indentation of the marker, which the comment lines share
+    indent: String,
+    // The lines of the comment, after the marker
+    lines: std::ops::Range<usize>,
+    // The text of the comment without the `//` of each line
+    text: String,
+}
+
+// The comment blocks of the source, each starting at a `// i18n: <key>` line,
+// or at `// i18n(<lang>): <key>` once it was translated
+fn blocks(source: &str) -> Vec<Block> {
+    let marker = Regex::new(MARKER_REGEX).unwrap();
+    let not_done = Regex::new(I_AM_NOT_DONE_REGEX).unwrap();
+    let lines: Vec<&str> = source.lines().collect();
+    let mut blocks = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let captures = match marker.captures(line) {
+            Some(captures) => captures,
+            None => continue,
+        };
+        let start = i + 1;
+        let end = (start..lines.len())
+            .find(|&j| {
+                let line = lines[j].trim_start();
+                !line.starts_with("//") || marker.is_match(lines[j]) || not_done.is_match(lines[j])
+            })
+            .unwrap_or(lines.len());
+        let text = lines[start..end]
+            .iter()
+            .map(|line| {
+                let comment = line.trim_start().trim_start_matches("//");
+                comment.strip_prefix(' ').unwrap_or(comment)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        blocks.push(Block {
+            key: captures[3].to_string(),
+            locale: captures
+                .get(2)
+                .map_or(SOURCE_LOCALE, |locale| locale.as_str())
+                .to_string(),
+            indent: captures[1].to_string(),
+            lines: start..end,
+            text,
+        });
+    }
+    blocks
+}
+
+// The exercise sources below the given directory
+fn collect_sources(dir: &Path, sources: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_sources(&path, sources);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            sources.push(path);
+        }
+    }
+}
+
+fn exercise_sources() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    collect_sources(Path::new(EXERCISES_DIR), &mut paths);
+    paths.sort();
+    paths
+}
+
+fn catalog_path(locale: &str) -> PathBuf {
+    Path::new(CATALOG_DIR).join(format!("{locale}.toml"))
+}
+
+fn read_catalog(locale: &str) -> Result<BTreeMap<String, String>, String> {
+    let path = catalog_path(locale);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| t!("common.read_failed", path = path.display(), error = e))?;
+    toml::from_str(&content).map_err(|e| t!("common.parse_failed", path = path.display(), error = e))
+}
+
+fn write_catalog(locale: &str, catalog: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = catalog_path(locale);
+    fs::create_dir_all(CATALOG_DIR)
+        .map_err(|e| t!("common.create_failed", path = CATALOG_DIR, error = e))?;
+    let content = toml::to_string_pretty(catalog).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| t!("common.write_failed", path = path.display(), error = e))
+}
+
+// Collect the comment blocks of all exercises into the English catalog, and
+// bring the catalogs of the other languages up to date with it: new blocks
+// are added untranslated, and blocks that no longer exist are dropped
+pub fn extract() -> Result<(), String> {
+    let mut english = BTreeMap::new();
+    for path in exercise_sources() {
+        let source = fs::read_to_string(&path)
+            .map_err(|e| t!("common.read_failed", path = path.display(), error = e))?;
+        for block in blocks(&source) {
+            if block.locale != SOURCE_LOCALE {
+                warn!(
+                    "{}",
+                    t!("localize.not_english", path = path.display(), key = block.key, locale = block.locale)
+                );
+                continue;
+            }
+            match english.get(&block.key) {
+                Some(text) if *text != block.text => {
+                    return Err(t!("localize.conflict", path = path.display(), key = block.key));
+                }
+                _ => {
+                    english.insert(block.key, block.text);
+                }
+            }
+        }
+    }
+    write_catalog(SOURCE_LOCALE, &english)?;
+    success!("{}", t!("localize.extracted", count = english.len(), path = catalog_path(SOURCE_LOCALE).display()));
+
+    for (locale, _) in LOCALES.iter().filter(|(locale, _)| *locale != SOURCE_LOCALE) {
+        let previous = read_catalog(locale)?;
+        let catalog: BTreeMap<String, String> = english
+            .keys()
+            .map(|key| (key.clone(), previous.get(key).cloned().unwrap_or_default()))
+            .collect();
+        let untranslated = catalog.values().filter(|text| text.is_empty()).count();
+        write_catalog(locale, &catalog)?;
+        println!(
+            "{}",
+            t!("localize.untranslated", path = catalog_path(locale).display(), count = untranslated)
+        );
+    }
+    Ok(())
+}
+
+// The source with its comment blocks in the given language, taken from its
+// catalog or else from the English one. Blocks without a text in either stay
+// as they are.
+fn localize(
+    source: &str,
+    locale: &str,
+    catalog: &BTreeMap<String, String>,
+    english: &BTreeMap<String, String>,
+) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut localized = Vec::new();
+    let mut next = 0;
+    for block in blocks(source) {
+        let (text, text_locale) = match catalog.get(&block.key).filter(|text| !text.is_empty()) {
+            Some(text) => (text, locale),
+            None => match english.get(&block.key) {
+                Some(text) => (text, SOURCE_LOCALE),
+                None => continue,
+            },
+        };
+        localized.extend(lines[next..block.lines.start - 1].iter().map(|line| line.to_string()));
+        let indent = &block.indent;
+        localized.push(if text_locale == SOURCE_LOCALE {
+            format!("{indent}// i18n: {}", block.key)
+        } else {
+            format!("{indent}// i18n({text_locale}): {}", block.key)
+        });
+        localized.extend(text.lines().map(|line| {
+            if line.is_empty() {
+                format!("{indent}//")
+            } else {
+                format!("{indent}// {line}")
+            }
+        }));
+        next = block.lines.end;
+    }
+    localized.extend(lines[next..].iter().map(|line| line.to_string()));
+    let mut localized = localized.join("\n");
+    if source.ends_with('\n') {
+        localized.push('\n');
+    }
+    localized
+}
+
+// Rewrite the comment blocks of every exercise file in the given language
+pub fn init(locale: &str) -> Result<(), String> {
+    let english = read_catalog(SOURCE_LOCALE)?;
+    if english.is_empty() {
+        return Err(t!("localize.no_catalog", path = catalog_path(SOURCE_LOCALE).display()));
+    }
+    let catalog = read_catalog(locale)?;
+    let mut changed = 0;
+    for path in exercise_sources() {
+        let source = fs::read_to_string(&path)
+            .map_err(|e| t!("common.read_failed", path = path.display(), error = e))?;
+        let localized = localize(&source, locale, &catalog, &english);
+        if localized != source {
+            fs::write(&path, localized)
+                .map_err(|e| t!("common.write_failed", path = path.display(), error = e))?;
+            changed += 1;
+        }
+    }
+    success!("{}", t!("localize.initialized", count = changed, locale = locale));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = "// intro.rs
+//
+// i18n: intro.about
+// Make the code print a greeting.
+//
+// Have fun!
+
+// I AM NOT DONE
+
+fn main() {
+    // i18n: intro.print
+    // Print it here
+    println!(\"Hello\");
+}
+";
+
+    fn catalog(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(key, text)| (key.to_string(), text.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_blocks_end_before_code() {
+        let blocks = blocks(SOURCE);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].key, "intro.about");
+        assert_eq!(blocks[0].text, "Make the code print a greeting.\n\nHave fun!");
+        assert_eq!(blocks[1].indent, "    ");
+        assert_eq!(blocks[1].text, "Print it here");
+    }
+
+    #[test]
+    fn test_localize_keeps_the_code() {
+        let english = catalog(&[
+            ("intro.about", "Make the code print a greeting.\n\nHave fun!"),
+            ("intro.print", "Print it here"),
+        ]);
+        let chinese = catalog(&[("intro.about", "让代码打印一句问候。"), ("intro.print", "")]);
+
+        let localized = localize(SOURCE, "zh-CN", &chinese, &english);
+        assert!(localized.contains("// i18n(zh-CN): intro.about\n// 让代码打印一句问候。\n\n// I AM NOT DONE"));
+        assert!(localized.contains("    // i18n: intro.print\n    // Print it here\n    println!"));
+        assert_eq!(blocks(&localized)[0].locale, "zh-CN");
+
+        // Going back to English gives the original source
+        assert_eq!(localize(&localized, "en", &english, &english), SOURCE);
+    }
+}
@@ -1,31 +1,48 @@
-use crate::exercise::{Exercise, ExerciseList};
+use crate::config::{Layer, Reporter};
+use crate::diff::{diff, diff_stat};
+use crate::editor::Editor;
+use crate::exercise::{Exercise, ExerciseList, RunOptions};
 use crate::project::RustAnalyzerProject;
-use crate::run::{reset, run};
-use crate::verify::verify;
+use crate::run::{reset, run, undo_reset};
+use crate::verify::{verify, verify_quietly};
+use crate::watch::{watch, WatchOptions, WatchStatus, DEFAULT_POLL_INTERVAL};
 use argh::FromArgs;
-use console::Emoji;
-use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
-use std::ffi::OsStr;
 use std::fs;
 use std::io::{self, prelude::*};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
 use std::time::{UNIX_EPOCH, SystemTime};
 
+#[macro_use]
+mod i18n;
 #[macro_use]
 mod ui;
 
+mod autocommit;
+mod backtrace;
+mod config;
+mod dashboard;
+mod debug;
+mod diff;
+mod editor;
 mod exercise;
+mod history;
+mod localize;
+mod model;
+mod prefetch;
+mod pristine;
+mod progress;
 mod project;
+mod root;
 mod run;
+mod select;
+mod serve;
+mod tui;
 mod verify;
+mod watch;
 
 // In sync with crate version
 const VERSION: &str = "5.5.1";
@@ -39,6 +56,15 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// the directory of the course, containing info.toml
+    #[argh(option)]
+    root: Option<PathBuf>,
+    /// the language of messages, like en or zh-CN
+    #[argh(option)]
+    lang: Option<String>,
+    /// plain output for screen readers and logs: no colors, emoji, spinners or redraws
+    #[argh(switch)]
+    plain: bool,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -49,10 +75,20 @@ enum Subcommands {
     Verify(VerifyArgs),
     Watch(WatchArgs),
     Run(RunArgs),
+    Debug(DebugArgs),
     Reset(ResetArgs),
+    UndoReset(UndoResetArgs),
+    Diff(DiffArgs),
+    History(HistoryArgs),
     Hint(HintArgs),
     List(ListArgs),
     Lsp(LspArgs),
+    EditorSetup(EditorSetupArgs),
+    Serve(ServeArgs),
+    Dashboard(DashboardArgs),
+    Config(ConfigArgs),
+    I18n(I18nArgs),
+    Init(InitArgs),
     CicvVerify(CicvVerifyArgs)
 }
 
@@ -62,8 +98,12 @@ struct CicvVerifyArgs {}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
-/// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+/// Verifies all exercises according to the recommended order, or the given ones
+struct VerifyArgs {
+    #[argh(positional)]
+    /// names, paths or glob patterns of the exercises to verify
+    names: Vec<String>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
@@ -72,6 +112,16 @@ struct WatchArgs {
     /// show hints on success
     #[argh(switch)]
     success_hints: bool,
+    /// commit an exercise to the local git repository when it starts passing
+    #[argh(switch)]
+    auto_commit: bool,
+    /// check for file changes periodically instead of relying on file system notifications,
+    /// every second with --poll or every given number of milliseconds with --poll=<ms>
+    #[argh(option, arg_name = "ms", from_str_fn(poll_interval))]
+    poll: Option<Duration>,
+    /// show a full-screen terminal UI instead of printing line by line
+    #[argh(switch)]
+    tui: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -79,17 +129,86 @@ struct WatchArgs {
 /// Runs/Tests a single exercise
 struct RunArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the name, path or glob pattern of the exercises
+    name: String,
+    #[argh(positional)]
+    /// arguments for the program, given after `--`
+    args: Vec<String>,
+    #[argh(option)]
+    /// only run the tests whose names contain this
+    test: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "debug")]
+/// Builds an exercise with debug info and starts rust-gdb or rust-lldb on it
+struct DebugArgs {
+    #[argh(positional)]
+    /// the name or path of the exercise
     name: String,
+    #[argh(option)]
+    /// only run the tests whose names contain this
+    test: Option<String>,
+    #[argh(switch)]
+    /// print the command starting the debugger instead of running it
+    print: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Resets exercises to their original source, keeping a backup of the current files
 struct ResetArgs {
+    #[argh(positional)]
+    /// the name, path or glob pattern of the exercises
+    name: Option<String>,
+    #[argh(option, short = 't')]
+    /// reset every exercise of the given topic
+    topic: Option<String>,
+    #[argh(switch, short = 'a')]
+    /// reset every exercise
+    all: bool,
+    #[argh(switch, short = 'y')]
+    /// do not ask for confirmation when resetting several exercises
+    yes: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "undo-reset")]
+/// Restores the files backed up by the last reset of exercises
+struct UndoResetArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: Option<String>,
+    #[argh(option, short = 't')]
+    /// restore every exercise of the given topic
+    topic: Option<String>,
+    #[argh(switch, short = 'a')]
+    /// restore every exercise that has a backup
+    all: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "diff")]
+/// Shows what changed compared to the original exercise
+struct DiffArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: Option<String>,
+    #[argh(switch)]
+    /// only show how many lines changed, for all exercises unless a name is given
+    stat: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "history")]
+/// Lists the snapshots taken each time an exercise was verified
+struct HistoryArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    #[argh(option, short = 'r')]
+    /// restore the snapshot with the given number
+    restore: Option<usize>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -97,7 +216,7 @@ struct ResetArgs {
 /// Returns a hint for the given exercise
 struct HintArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the name, path or glob pattern of the exercises
     name: String,
 }
 
@@ -106,6 +225,119 @@ struct HintArgs {
 /// Enable rust-analyzer for exercises
 struct LspArgs {}
 
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "editor-setup")]
+/// Adds tasks running the exercise of the active file to the editor: vscode, helix or nvim
+struct EditorSetupArgs {
+    #[argh(positional)]
+    /// the editor to set up
+    editor: Editor,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "serve")]
+/// Serves the exercises to editor plugins over JSON-RPC
+struct ServeArgs {
+    #[argh(switch)]
+    /// speak JSON-RPC on stdin and stdout, one message per line
+    stdio: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "dashboard")]
+/// Shows your progress and the current exercise on a web page on localhost
+struct DashboardArgs {
+    #[argh(option, short = 'p')]
+    /// the port to serve the page on, 7373 unless given
+    port: Option<u16>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "config")]
+/// Shows or changes the settings in rustlings.toml and ~/.config/rustlings/config.toml
+struct ConfigArgs {
+    #[argh(subcommand)]
+    nested: ConfigSubcommands,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum ConfigSubcommands {
+    Get(ConfigGetArgs),
+    Set(ConfigSetArgs),
+    Unset(ConfigUnsetArgs),
+    List(ConfigListArgs),
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "get")]
+/// Shows the value of a setting
+struct ConfigGetArgs {
+    #[argh(positional)]
+    /// the name of the setting
+    key: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "set")]
+/// Changes a setting of the project, or of the user with --global
+struct ConfigSetArgs {
+    #[argh(positional)]
+    /// the name of the setting
+    key: String,
+    #[argh(positional)]
+    /// the new value
+    value: String,
+    #[argh(switch, short = 'g')]
+    /// change the setting for all of your courses
+    global: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "unset")]
+/// Removes a setting of the project, or of the user with --global
+struct ConfigUnsetArgs {
+    #[argh(positional)]
+    /// the name of the setting
+    key: String,
+    #[argh(switch, short = 'g')]
+    /// remove the setting for all of your courses
+    global: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "list")]
+/// Lists the settings that are set, and where
+struct ConfigListArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "i18n")]
+/// Maintains the translations of the comments in exercises
+struct I18nArgs {
+    #[argh(subcommand)]
+    nested: I18nSubcommands,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum I18nSubcommands {
+    Extract(I18nExtractArgs),
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "extract")]
+/// Collects the comments marked with `// i18n: <key>` into locales/exercises/
+struct I18nExtractArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "init")]
+/// Rewrites the comments of the exercises in the given language, leaving the code as it is
+struct InitArgs {
+    #[argh(option)]
+    /// the language of the comments, like en or zh-CN, by default the one of messages
+    lang: Option<String>,
+}
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "list")]
 /// Lists the exercises available in Rustlings
@@ -138,6 +370,9 @@ pub struct ExerciseCheckList {
 #[derive(Deserialize, Serialize)]
 pub struct ExerciseResult {
     pub name: String,
+    // The title in the language in use, for exercises that have one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     pub result: bool
 }
 
@@ -151,45 +386,74 @@ pub struct  ExerciseStatistics {
 
 #[tokio::main]
 async fn main() {
-    let args: Args = argh::from_env();
+    let args = parse_args();
 
     if args.version {
         println!("v{VERSION}");
         std::process::exit(0);
     }
 
-    if args.nested.is_none() {
-        println!("\n{WELCOME}\n");
+    // The configuration can only be read in the course, until then the
+    // language is the one given on the command line or by the environment
+    let _ignored = i18n::init(args.lang.as_deref(), None);
+
+    // Everything rustlings reads and writes, like the exercises and its
+    // state in `.rustlings/`, is relative to the root of the course
+    let root = match root::find(args.root.as_deref()) {
+        Ok(root) => root,
+        Err(e) => {
+            println!("{e}");
+            std::process::exit(1);
+        }
+    };
+    if let Ok(dir) = std::env::current_dir() {
+        select::init(&dir, &root);
     }
-
-    if !Path::new("info.toml").exists() {
-        println!(
-            "{} must be run from the rustlings directory",
-            std::env::current_exe().unwrap().to_str().unwrap()
-        );
-        println!("Try `cd rustlings/`!");
+    if let Err(e) = std::env::set_current_dir(&root) {
+        println!("{}", t!("main.change_dir_failed", dir = root.display(), error = e));
         std::process::exit(1);
     }
 
     if !rustc_exists() {
-        println!("We cannot find `rustc`.");
-        println!("Try running `rustc --version` to diagnose your problem.");
-        println!("For instructions on how to install Rust, check the README.");
+        println!("{}", t!("main.no_rustc"));
         std::process::exit(1);
     }
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-    let verbose = args.nocapture;
+    let exercises = match ExerciseList::load(Path::new("info.toml")) {
+        Ok(list) => list.exercises,
+        Err(e) => {
+            println!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let loaded = config::load();
+    let config = config::get();
+    ui::init(args.plain || config.plain(), config.emoji, config.color);
+    if let Err(e) = i18n::init(args.lang.as_deref(), config.locale.as_deref()) {
+        warn!("{}", e);
+    }
+    if let Err(e) = loaded {
+        warn!("{}", t!("main.config_invalid", error = e));
+    }
+
+    if args.nested.is_none() {
+        ui::art(WELCOME);
+    }
+    let verbose = args.nocapture || config.nocapture();
 
     let command = args.nested.unwrap_or_else(|| {
-        println!("{DEFAULT_OUT}\n");
+        println!("{}\n", t!("main.intro"));
         std::process::exit(0);
     });
     match command {
         Subcommands::List(subargs) => {
             if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+                println!(
+                    "{:<17}\t{:<46}\t{:<7}",
+                    t!("list.name"),
+                    t!("list.path"),
+                    t!("list.status")
+                );
             }
             let mut exercises_done: u16 = 0;
             let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
@@ -201,9 +465,9 @@ async fn main() {
                     .any(|f| e.name.contains(&f) || fname.contains(&f));
                 let status = if e.looks_done() {
                     exercises_done += 1;
-                    "Done"
+                    t!("list.done")
                 } else {
-                    "Pending"
+                    t!("list.pending")
                 };
                 let solve_cond = {
                     (e.looks_done() && subargs.solved)
@@ -235,34 +499,171 @@ async fn main() {
             });
             let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
             println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
+                "{}",
+                t!(
+                    "list.progress",
+                    done = exercises_done,
+                    total = exercises.len(),
+                    percentage = format!("{percentage_progress:.1}")
+                )
             );
             std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
+            let options = RunOptions {
+                args: subargs.args,
+                test_filter: subargs.test,
+                interactive: true,
+            };
+            for exercise in find_exercises(&subargs.name, &exercises) {
+                run(exercise, verbose, &options).unwrap_or_else(|_| std::process::exit(1));
+            }
+        }
+
+        Subcommands::Debug(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            debug::debug(exercise, subargs.test.as_deref(), subargs.print).unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Reset(subargs) => {
+            let selected = select_exercises(&subargs.name, &subargs.topic, subargs.all, &exercises);
+            if selected.len() > 1
+                && !subargs.yes
+                && !confirm(&t!("main.confirm_reset", count = selected.len()))
+            {
+                std::process::exit(1);
+            }
+
+            let failures = selected.iter().filter(|e| reset(e).is_err()).count();
+            if failures > 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::UndoReset(subargs) => {
+            let selected = select_exercises(&subargs.name, &subargs.topic, subargs.all, &exercises);
+            let failures = if subargs.name.is_some() {
+                selected.iter().filter(|e| undo_reset(e).is_err()).count()
+            } else {
+                selected
+                    .iter()
+                    .filter(|e| pristine::backup_path(&e.path).exists())
+                    .filter(|e| undo_reset(e).is_err())
+                    .count()
+            };
+            if failures > 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Diff(subargs) => {
+            let result = match (&subargs.name, subargs.stat) {
+                (Some(name), false) => diff(find_exercise(name, &exercises)),
+                (Some(name), true) => {
+                    diff_stat([find_exercise(name, &exercises)]);
+                    Ok(())
+                }
+                (None, true) => {
+                    diff_stat(&exercises);
+                    Ok(())
+                }
+                (None, false) => {
+                    println!("{}", t!("main.diff_usage"));
+                    Err(())
+                }
+            };
+            result.unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::History(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
 
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            match subargs.restore {
+                Some(number) => history::restore(exercise, number)
+                    .unwrap_or_else(|_| std::process::exit(1)),
+                None => history::list(exercise),
+            }
         }
 
         Subcommands::Hint(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let selected = find_exercises(&subargs.name, &exercises);
+            for exercise in &selected {
+                if selected.len() > 1 {
+                    println!("{}", ui::style(&exercise.name).bold());
+                }
+                println!("{}", exercise.hint);
+            }
+        }
 
-            println!("{}", exercise.hint);
+        Subcommands::Verify(subargs) => {
+            let selected: Vec<&Exercise> = if subargs.names.is_empty() {
+                exercises.iter().collect()
+            } else {
+                let given: Vec<&Exercise> = subargs
+                    .names
+                    .iter()
+                    .flat_map(|name| find_exercises(name, &exercises))
+                    .collect();
+                // In the order of info.toml, each exercise once
+                exercises.iter().filter(|e| given.iter().any(|g| g.name == e.name)).collect()
+            };
+            match config.reporter() {
+                Reporter::Pretty => {
+                    verify(selected.iter().copied(), (0, selected.len()), verbose, false)
+                        .unwrap_or_else(|_| std::process::exit(1));
+                }
+                Reporter::Json => {
+                    let mut all_done = true;
+                    for exercise in selected {
+                        let result = verify_quietly(exercise).passed && exercise.looks_done();
+                        all_done &= result;
+                        let line = ExerciseResult {
+                            name: exercise.name.clone(),
+                            title: exercise.title.as_ref().map(ToString::to_string),
+                            result,
+                        };
+                        println!("{}", serde_json::to_string(&line).unwrap());
+                    }
+                    if !all_done {
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+
+        Subcommands::Config(subargs) => {
+            let layer = |global| if global { Layer::User } else { Layer::Project };
+            let result = match subargs.nested {
+                ConfigSubcommands::Get(args) => config::print_value(&args.key),
+                ConfigSubcommands::List(_) => config::print_all(),
+                ConfigSubcommands::Set(args) => config::set(layer(args.global), &args.key, Some(&args.value))
+                    .map(|path| success!("{}", t!("main.setting_saved", path = path.display()))),
+                ConfigSubcommands::Unset(args) => config::set(layer(args.global), &args.key, None)
+                    .map(|path| success!("{}", t!("main.setting_removed", path = path.display()))),
+            };
+            if let Err(e) = result {
+                warn!("{}", e);
+                std::process::exit(1);
+            }
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose, false)
-                .unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::I18n(subargs) => match subargs.nested {
+            I18nSubcommands::Extract(_) => localize::extract().unwrap_or_else(|e| {
+                warn!("{}", e);
+                std::process::exit(1);
+            }),
+        },
+
+        Subcommands::Init(subargs) => {
+            let locale = match subargs.lang {
+                Some(lang) => i18n::resolve(&lang),
+                None => Ok(i18n::locale()),
+            };
+            if let Err(e) = locale.and_then(localize::init) {
+                warn!("{}", e);
+                std::process::exit(1);
+            }
         }
 
         Subcommands::CicvVerify(_subargs) => {
@@ -286,34 +687,39 @@ async fn main() {
             ));
 
             let mut tasks = vec![];
+            let jobs = Arc::new(tokio::sync::Semaphore::new(config.jobs()));
             for exercise in exercises {
                 let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
                 let inner_exercise = exercise;
                 let c_mutex = Arc::clone(&rights);
                 let exercise_check_list_ref = Arc::clone(&exercise_check_list);
                 let _verbose = verbose.clone();
+                let jobs = Arc::clone(&jobs);
                 let t = tokio::task::spawn( async move {
-                    match run(&inner_exercise, true) {
+                    let _job = jobs.acquire().await.unwrap();
+                    match run(&inner_exercise, true, &RunOptions::default()) {
                     // match verify(vec![&inner_exercise], (0, 1), true, true) {
                         Ok(_) => {
                             *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
+                            println!("{}", t!("cicv.passed", exercise = inner_exercise.name));
+                            println!("{}", t!("cicv.total", total = alls));
+                            println!("{}", t!("cicv.correct", count = *c_mutex.lock().unwrap()));
                             let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
+                            println!("{}", t!("cicv.elapsed", seconds = now_end - now_start));
                             exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
+                                title: inner_exercise.title.as_ref().map(ToString::to_string),
                                 name: inner_exercise.name, result: true,
                             });
                             exercise_check_list_ref.lock().unwrap().statistics.total_succeeds += 1;
                         },
                         Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
+                            println!("{}", t!("cicv.failed", exercise = inner_exercise.name));
+                            println!("{}", t!("cicv.total", total = alls));
+                            println!("{}", t!("cicv.correct", count = *c_mutex.lock().unwrap()));
                             let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
+                            println!("{}", t!("cicv.elapsed", seconds = now_end - now_start));
                             exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
+                                title: inner_exercise.title.as_ref().map(ToString::to_string),
                                 name: inner_exercise.name, result: false,
                             });
                             exercise_check_list_ref.lock().unwrap().statistics.total_failures += 1;
@@ -325,7 +731,7 @@ async fn main() {
             for task in tasks { task.await.unwrap(); }
             let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
             let total_time = now_end - now_start;
-            println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time);
+            println!("{}", t!("cicv.finished", seconds = total_time));
             let exercise_check_list_ref = Arc::clone(&exercise_check_list);
             exercise_check_list_ref.lock().unwrap().statistics.total_time = total_time as u32;
             let serialized = serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
@@ -336,192 +742,189 @@ async fn main() {
             let mut project = RustAnalyzerProject::new();
             project
                 .get_sysroot_src()
-                .expect("Couldn't find toolchain path, do you have `rustc` installed?");
-            project
-                .exercises_to_json()
-                .expect("Couldn't parse rustlings exercises files");
+                .unwrap_or_else(|_| panic!("{}", t!("lsp.no_sysroot")));
+            project.exercises_to_json(&exercises);
 
             if project.crates.is_empty() {
-                println!("Failed find any exercises, make sure you're in the `rustlings` folder");
+                println!("{}", t!("lsp.no_exercises"));
             } else if project.write_to_disk().is_err() {
-                println!("Failed to write rust-project.json to disk for rust-analyzer");
+                println!("{}", t!("lsp.write_failed"));
             } else {
-                println!("Successfully generated rust-project.json");
-                println!("rust-analyzer will now parse exercises, restart your language server or editor")
+                println!("{}", t!("lsp.generated"));
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
-            Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+        Subcommands::EditorSetup(subargs) => editor::setup(subargs.editor).unwrap_or_else(|e| {
+            warn!("{}", e);
+            std::process::exit(1);
+        }),
+
+        Subcommands::Serve(subargs) => {
+            if !subargs.stdio {
+                warn!("{}", t!("serve.no_transport"));
                 std::process::exit(1);
             }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
+            serve::serve(exercises);
+        }
+
+        Subcommands::Dashboard(subargs) => {
+            dashboard::dashboard(&exercises, subargs.port.unwrap_or(dashboard::DEFAULT_PORT)).unwrap_or_else(|e| {
+                warn!("{}", e);
+                std::process::exit(1);
+            })
+        }
+
+        Subcommands::Watch(subargs) => match watch(
+            &exercises,
+            &WatchOptions {
+                verbose,
+                success_hints: subargs.success_hints || config.success_hints(),
+                auto_commit: subargs.auto_commit || config.auto_commit(),
+                poll_interval: subargs.poll,
+                tui: subargs.tui,
+            },
+        ) {
+            WatchStatus::Finished => {
+                println!("{}", t!("main.all_done", emoji = ui::symbol("🎉", "★")));
+                ui::art(FENISH_LINE);
             }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+            WatchStatus::Unfinished => {
+                println!("{}", t!("main.watch_unfinished"));
             }
         },
     }
 }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
-    thread::spawn(move || loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
-                    }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else if let Some(cmd) = input.strip_prefix('!') {
-                    let parts: Vec<&str> = cmd.split_whitespace().collect();
-                    if parts.is_empty() {
-                        println!("no command provided");
-                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
-                        println!("failed to execute command `{}`: {}", cmd, e);
-                    }
-                } else {
-                    println!("unknown command: {input}");
-                }
+// The exercises given by name, path or glob pattern, or the next pending one
+// for `next`. Exits with suggestions for what might have been meant if none
+// matches.
+// Like `argh::from_env`, except that `watch --poll` may be given without a
+// value, which argh does not support for options: `--poll` without a value
+// is passed on with an empty one, and `--poll=<ms>` is split into the option
+// and its value
+fn parse_args() -> Args {
+    let mut strings: Vec<String> = Vec::new();
+    let mut args = std::env::args().peekable();
+    let mut in_watch = false;
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--poll") {
+            Some("") if in_watch && args.peek().map_or(true, |next| next.starts_with('-')) => {
+                strings.extend([arg, String::new()]);
+            }
+            Some(value) if in_watch && value.starts_with('=') => {
+                strings.extend(["--poll".to_string(), value[1..].to_string()]);
+            }
+            _ => {
+                in_watch |= arg == "watch";
+                strings.push(arg);
             }
-            Err(error) => println!("error reading command: {error}"),
         }
-    });
+    }
+
+    let cmd = Path::new(&strings[0])
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(&strings[0]);
+    let strs: Vec<&str> = strings.iter().map(|s| s.as_str()).collect();
+    Args::from_args(&[cmd], &strs[1..]).unwrap_or_else(|early_exit| {
+        std::process::exit(match early_exit.status {
+            Ok(()) => {
+                println!("{}", early_exit.output);
+                0
+            }
+            Err(()) => {
+                eprintln!("{}\nRun {} --help for more information.", early_exit.output, cmd);
+                1
+            }
+        })
+    })
 }
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
+// The interval of `watch --poll`, which is empty when no interval was given
+fn poll_interval(value: &str) -> Result<Duration, String> {
+    if value.is_empty() {
+        return Ok(DEFAULT_POLL_INTERVAL);
+    }
+    match value.parse() {
+        Ok(ms) if ms > 0 => Ok(Duration::from_millis(ms)),
+        _ => Err(t!("main.invalid_poll_interval", value = value)),
+    }
+}
+
+fn find_exercises<'a>(name: &str, exercises: &'a [Exercise]) -> Vec<&'a Exercise> {
     if name.eq("next") {
-        exercises
+        let next = exercises
             .iter()
             .find(|e| !e.looks_done())
             .unwrap_or_else(|| {
-                println!("🎉 Congratulations! You have done all the exercises!");
-                println!("🔚 There are no more exercises to do next!");
+                println!("{}", t!("main.no_next", emoji = ui::symbol("🎉", "★"), end = ui::symbol("🔚", "-")));
                 std::process::exit(1)
-            })
-    } else {
-        exercises
-            .iter()
-            .find(|e| e.name == name)
-            .unwrap_or_else(|| {
-                println!("No exercise found for '{name}'!");
-                std::process::exit(1)
-            })
+            });
+        return vec![next];
     }
+    let found = select::matching(name, exercises);
+    if found.is_empty() {
+        println!("{}", t!("main.no_exercise_found", name = name));
+        let suggestions = select::suggestions(name, exercises);
+        if !suggestions.is_empty() {
+            println!("{}", t!("main.did_you_mean", names = suggestions.join(", ")));
+        }
+        std::process::exit(1);
+    }
+    found
 }
 
-enum WatchStatus {
-    Finished,
-    Unfinished,
-}
-
-fn watch(
-    exercises: &[Exercise],
-    verbose: bool,
-    success_hints: bool,
-) -> notify::Result<WatchStatus> {
-    /* Clears the terminal with an ANSI escape code.
-    Works in UNIX and newer Windows terminals. */
-    fn clear_screen() {
-        println!("\x1Bc");
+// The single exercise given by name or path, see `find_exercises`
+fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
+    match find_exercises(name, exercises)[..] {
+        [exercise] => exercise,
+        ref several => {
+            let names: Vec<&str> = several.iter().map(|e| e.name.as_str()).collect();
+            println!("{}", t!("main.several_exercises", name = name, names = names.join(", ")));
+            std::process::exit(1)
+        }
     }
+}
 
-    let (tx, rx) = channel();
-    let should_quit = Arc::new(AtomicBool::new(false));
-
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
-
-    clear_screen();
-
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
-        exercises.iter(),
-        (0, exercises.len()),
-        verbose,
-        success_hints,
-    ) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
-    };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
-    loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            },
-            Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
+// Select the exercises given by name, by topic, or all of them
+fn select_exercises<'a>(
+    name: &Option<String>,
+    topic: &Option<String>,
+    all: bool,
+    exercises: &'a [Exercise],
+) -> Vec<&'a Exercise> {
+    match (name, topic) {
+        (Some(name), None) if !all => find_exercises(name, exercises),
+        (None, Some(topic)) if !all => {
+            let selected: Vec<&Exercise> = exercises.iter().filter(|e| &e.topic() == topic).collect();
+            if selected.is_empty() {
+                println!("{}", t!("main.no_topic_found", topic = topic));
+                std::process::exit(1);
             }
-            Err(e) => println!("watch error: {e:?}"),
+            selected
         }
-        // Check if we need to exit
-        if should_quit.load(Ordering::SeqCst) {
-            return Ok(WatchStatus::Unfinished);
+        (None, None) if all => exercises.iter().collect(),
+        _ => {
+            println!("{}", t!("main.select_usage"));
+            std::process::exit(1);
         }
     }
 }
 
+// Ask the user a yes/no question on the terminal, defaulting to no
+fn confirm(question: &str) -> bool {
+    print!("{question} {} ", t!("main.yes_no"));
+    io::stdout().flush().unwrap();
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(_) => {
+            let answer = answer.trim().to_lowercase();
+            matches!(answer.as_str(), "y" | "yes") || answer == t!("main.yes")
+        }
+        Err(_) => false,
+    }
+}
+
 fn rustc_exists() -> bool {
     Command::new("rustc")
         .args(&["--version"])
@@ -532,34 +935,6 @@ fn rustc_exists() -> bool {
         .unwrap_or(false)
 }
 
-const DEFAULT_OUT: &str = r#"Thanks for installing Rustlings!
-
-Is this your first time? Don't worry, Rustlings was made for beginners! We are
-going to teach you a lot of things about Rust, but before we can get
-started, here's a couple of notes about how Rustlings operates:
-
-1. The central concept behind Rustlings is that you solve exercises. These
-   exercises usually have some sort of syntax error in them, which will cause
-   them to fail compilation or testing. Sometimes there's a logic error instead
-   of a syntax error. No matter what error, it's your job to find it and fix it!
-   You'll know when you fixed it because then, the exercise will compile and
-   Rustlings will be able to move on to the next exercise.
-2. If you run Rustlings in watch mode (which we recommend), it'll automatically
-   start with the first exercise. Don't get confused by an error message popping
-   up as soon as you run Rustlings! This is part of the exercise that you're
-   supposed to solve, so open the exercise file in an editor and start your
-   detective work!
-3. If you're stuck on an exercise, there is a helpful hint you can view by typing
-   'hint' (in watch mode), or running `rustlings hint exercise_name`.
-4. If an exercise doesn't make sense to you, feel free to open an issue on GitHub!
-   (https://github.com/rust-lang/rustlings/issues/new). We look at every issue,
-   and sometimes, other learners do too so you can help each other out!
-5. If you want to use `rust-analyzer` with exercises, which provides features like
-   autocompletion, run the command `rustlings lsp`.
-
-Got all that? Great! To get started, run `rustlings watch` in order to get the first
-exercise. Make sure to have your editor open!"#;
-
 const FENISH_LINE: &str = r#"+----------------------------------------------------+
 |          You made it to the Fe-nish line!          |
 +--------------------------  ------------------------+
@@ -0,0 +1,391 @@
+use serde::Deserialize;
+use std::env;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// How many random inputs are tried before an exercise is considered
+// to behave like its reference model
+pub const MODEL_CASES: u32 = 200;
+
+// The name of the generated test inside the model harness
+pub const MODEL_TEST_FILTER: &str = "__rustlings_model::";
+
+// How long the checks may run when no timeout is configured
+pub const MODEL_TIMEOUT: Duration = Duration::from_secs(30);
+
+// A reference model an exercise is compared against with random inputs.
+// This is deserialized from the `model` key of an exercise in info.toml
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Model {
+    // `fn sort(&mut [T])` behaves like `slice::sort`
+    Sort,
+    // `LinkedList::merge` of two sorted lists behaves like sorting their concatenation
+    SortedMerge,
+    // `LinkedList::reverse` behaves like `Vec::reverse`
+    Reverse,
+    // `BinarySearchTree::{insert, search}` behave like `BTreeSet::{insert, contains}`
+    BinarySearchTree,
+    // `fn bracket_match(&str) -> bool` behaves like a reference stack matcher
+    BracketMatch,
+    // `UndirectedGraph::add_edge` behaves like a reference adjacency list
+    UndirectedGraph,
+}
+
+impl Model {
+    // The Rust source of the model specific part of the harness.
+    // Every model provides `MODEL`, `RANGE`, `describe` and `check`.
+    fn checks(self) -> &'static str {
+        match self {
+            Model::Sort => SORT_CHECKS,
+            Model::SortedMerge => SORTED_MERGE_CHECKS,
+            Model::Reverse => REVERSE_CHECKS,
+            Model::BinarySearchTree => BINARY_SEARCH_TREE_CHECKS,
+            Model::BracketMatch => BRACKET_MATCH_CHECKS,
+            Model::UndirectedGraph => UNDIRECTED_GRAPH_CHECKS,
+        }
+    }
+}
+
+// The seed for the random inputs. `RUSTLINGS_SEED` reproduces a previous run.
+pub fn seed() -> u64 {
+    env::var("RUSTLINGS_SEED")
+        .ok()
+        .and_then(|seed| seed.trim().parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0)
+        })
+}
+
+// Append the property checks of the given model to the exercise source.
+// The checks live in a child module so that they can reach the private
+// items of the exercise, and are appended so that line numbers in
+// compiler messages still point into the exercise.
+pub fn harness(source: &str, model: Model, seed: u64, cases: u32) -> String {
+    format!(
+        "{source}\n\n#[cfg(test)]\n#[allow(dead_code, unused_imports, unused_variables)]\nmod __rustlings_model {{\n    use super::*;\n\n    const SEED: u64 = {seed};\n    const CASES: u32 = {cases};\n{checks}{HARNESS}}}\n",
+        checks = model.checks(),
+    )
+}
+
+const HARNESS: &str = r#"
+    struct Rng(u64);
+
+    impl Rng {
+        // xorshift64*
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Vec<i64> {
+        let len = rng.below(size as u64 + 1) as usize;
+        (0..len)
+            .map(|_| rng.below(2 * RANGE as u64 + 1) as i64 - RANGE)
+            .collect()
+    }
+
+    fn fails(input: &[i64]) -> Option<String> {
+        match std::panic::catch_unwind(|| check(input)) {
+            Ok(Ok(())) => None,
+            Ok(Err(message)) => Some(message),
+            Err(payload) => Some(match payload.downcast::<String>() {
+                Ok(message) => format!("panicked: {message}"),
+                Err(payload) => match payload.downcast::<&str>() {
+                    Ok(message) => format!("panicked: {message}"),
+                    Err(_) => "panicked".to_string(),
+                },
+            }),
+        }
+    }
+
+    // Smaller variants of a failing input: shorter first, then smaller values
+    fn candidates(input: &[i64]) -> Vec<Vec<i64>> {
+        let mut out = Vec::new();
+        let mut chunk = input.len();
+        while chunk > 0 {
+            for start in (0..input.len()).step_by(chunk) {
+                let mut candidate = input.to_vec();
+                candidate.drain(start..(start + chunk).min(input.len()));
+                out.push(candidate);
+            }
+            chunk /= 2;
+        }
+        for (i, &value) in input.iter().enumerate() {
+            for smaller in [0, value / 2, value - value.signum()] {
+                if smaller.abs() < value.abs() {
+                    let mut candidate = input.to_vec();
+                    candidate[i] = smaller;
+                    out.push(candidate);
+                }
+            }
+        }
+        out
+    }
+
+    fn shrink(mut input: Vec<i64>, mut message: String) -> (Vec<i64>, String) {
+        'shrinking: loop {
+            for candidate in candidates(&input) {
+                if let Some(smaller_message) = fails(&candidate) {
+                    input = candidate;
+                    message = smaller_message;
+                    continue 'shrinking;
+                }
+            }
+            return (input, message);
+        }
+    }
+
+    #[test]
+    fn rustlings_model_check() {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        let mut rng = Rng(SEED | 1);
+        let mut failure = None;
+        for case in 0..CASES {
+            let input = generate(&mut rng, 1 + case as usize / 4);
+            if let Some(message) = fails(&input) {
+                let (input, message) = shrink(input, message);
+                failure = Some((case + 1, input, message));
+                break;
+            }
+        }
+        std::panic::set_hook(hook);
+
+        if let Some((cases, input, message)) = failure {
+            // Written past the output capture of the test harness so that
+            // anything the exercise printed does not bury the counterexample
+            let report = format!(
+                "Behaves differently from the reference model ({MODEL}) after {cases} random case(s).\n\nMinimized counterexample:\n    {}\n{message}\n\nRerun with RUSTLINGS_SEED={SEED} to reproduce.\n",
+                describe(&input)
+            );
+            std::io::Write::write_all(&mut std::io::stderr(), report.as_bytes()).unwrap();
+            panic!("reference model check failed");
+        }
+    }
+"#;
+
+const SORT_CHECKS: &str = r#"
+    const MODEL: &str = "slice::sort";
+    const RANGE: i64 = 50;
+
+    fn describe(input: &[i64]) -> String {
+        format!("sort(&mut {input:?})")
+    }
+
+    fn check(input: &[i64]) -> Result<(), String> {
+        let mut expected = input.to_vec();
+        expected.sort();
+        let mut actual = input.to_vec();
+        sort(&mut actual);
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!("expected: {expected:?}\n  actual: {actual:?}"))
+        }
+    }
+"#;
+
+const SORTED_MERGE_CHECKS: &str = r#"
+    const MODEL: &str = "sorted concatenation";
+    const RANGE: i64 = 50;
+
+    fn lists(input: &[i64]) -> (Vec<i64>, Vec<i64>) {
+        let (a, b) = input.split_at(input.len() / 2);
+        let (mut a, mut b) = (a.to_vec(), b.to_vec());
+        a.sort();
+        b.sort();
+        (a, b)
+    }
+
+    fn describe(input: &[i64]) -> String {
+        let (a, b) = lists(input);
+        format!("LinkedList::merge({a:?}, {b:?})")
+    }
+
+    fn check(input: &[i64]) -> Result<(), String> {
+        let (a, b) = lists(input);
+        let mut list_a = LinkedList::<i64>::new();
+        a.iter().for_each(|&value| list_a.add(value));
+        let mut list_b = LinkedList::<i64>::new();
+        b.iter().for_each(|&value| list_b.add(value));
+        let merged = LinkedList::<i64>::merge(list_a, list_b);
+
+        let mut sorted: Vec<i64> = a.iter().chain(&b).copied().collect();
+        sorted.sort();
+        let mut expected: Vec<Option<i64>> = sorted.into_iter().map(Some).collect();
+        expected.push(None);
+        let actual: Vec<Option<i64>> = (0..expected.len() as i32)
+            .map(|i| merged.get(i).copied())
+            .collect();
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!("expected get(0..): {expected:?}\n  actual get(0..): {actual:?}"))
+        }
+    }
+"#;
+
+const REVERSE_CHECKS: &str = r#"
+    const MODEL: &str = "Vec::reverse";
+    const RANGE: i64 = 50;
+
+    fn describe(input: &[i64]) -> String {
+        format!("LinkedList::from({input:?}).reverse()")
+    }
+
+    fn check(input: &[i64]) -> Result<(), String> {
+        let mut list = LinkedList::<i64>::new();
+        input.iter().for_each(|&value| list.add(value));
+        list.reverse();
+
+        let mut expected: Vec<Option<i64>> = input.iter().map(|&value| Some(value)).collect();
+        expected.reverse();
+        expected.push(None);
+        let actual: Vec<Option<i64>> = (0..expected.len() as i32)
+            .map(|i| list.get(i).copied())
+            .collect();
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!("expected get(0..): {expected:?}\n  actual get(0..): {actual:?}"))
+        }
+    }
+"#;
+
+const BINARY_SEARCH_TREE_CHECKS: &str = r#"
+    const MODEL: &str = "BTreeSet";
+    const RANGE: i64 = 20;
+
+    // Non-negative values are inserted, negative values `-v - 1` are searched
+    fn describe(input: &[i64]) -> String {
+        let ops: Vec<String> = input
+            .iter()
+            .map(|&v| if v >= 0 { format!("insert({v})") } else { format!("search({})", -v - 1) })
+            .collect();
+        format!("[{}]", ops.join(", "))
+    }
+
+    fn check(input: &[i64]) -> Result<(), String> {
+        let mut tree = BinarySearchTree::<i64>::new();
+        let mut set = std::collections::BTreeSet::new();
+        for &v in input {
+            if v >= 0 {
+                tree.insert(v);
+                set.insert(v);
+            } else if tree.search(-v - 1) != set.contains(&(-v - 1)) {
+                return Err(format!("search({}) expected {}", -v - 1, set.contains(&(-v - 1))));
+            }
+        }
+        for value in 0..=RANGE {
+            if tree.search(value) != set.contains(&value) {
+                return Err(format!(
+                    "after all operations, search({value}) expected {}",
+                    set.contains(&value)
+                ));
+            }
+        }
+        Ok(())
+    }
+"#;
+
+const BRACKET_MATCH_CHECKS: &str = r#"
+    const MODEL: &str = "stack based bracket matcher";
+    const RANGE: i64 = 6;
+
+    fn text(input: &[i64]) -> String {
+        input
+            .iter()
+            .map(|v| ['(', ')', '[', ']', '{', '}', 'x'][v.rem_euclid(7) as usize])
+            .collect()
+    }
+
+    fn describe(input: &[i64]) -> String {
+        format!("bracket_match({:?})", text(input))
+    }
+
+    fn reference(text: &str) -> bool {
+        let mut stack = Vec::new();
+        for c in text.chars() {
+            match c {
+                '(' | '[' | '{' => stack.push(c),
+                ')' => if stack.pop() != Some('(') { return false },
+                ']' => if stack.pop() != Some('[') { return false },
+                '}' => if stack.pop() != Some('{') { return false },
+                _ => {}
+            }
+        }
+        stack.is_empty()
+    }
+
+    fn check(input: &[i64]) -> Result<(), String> {
+        let text = text(input);
+        let expected = reference(&text);
+        let actual = bracket_match(&text);
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!("expected: {expected}\n  actual: {actual}"))
+        }
+    }
+"#;
+
+const UNDIRECTED_GRAPH_CHECKS: &str = r#"
+    const MODEL: &str = "reference adjacency list";
+    const RANGE: i64 = 9;
+
+    // Every three values form an edge between two of five nodes
+    fn edges(input: &[i64]) -> Vec<(String, String, i32)> {
+        input
+            .chunks_exact(3)
+            .map(|e| (format!("n{}", e[0].rem_euclid(5)), format!("n{}", e[1].rem_euclid(5)), e[2] as i32))
+            .collect()
+    }
+
+    fn describe(input: &[i64]) -> String {
+        let calls: Vec<String> = edges(input)
+            .iter()
+            .map(|(from, to, weight)| format!("add_edge(({from:?}, {to:?}, {weight}))"))
+            .collect();
+        format!("[{}]", calls.join(", "))
+    }
+
+    fn check(input: &[i64]) -> Result<(), String> {
+        let mut graph = <UndirectedGraph as Graph>::new();
+        let mut expected_nodes = std::collections::BTreeSet::new();
+        let mut expected_edges = Vec::new();
+        for (from, to, weight) in edges(input) {
+            graph.add_edge((from.as_str(), to.as_str(), weight));
+            expected_nodes.insert(from.clone());
+            expected_nodes.insert(to.clone());
+            expected_edges.push((from.clone(), to.clone(), weight));
+            expected_edges.push((to, from, weight));
+        }
+        expected_edges.sort();
+
+        let actual_nodes: std::collections::BTreeSet<String> =
+            graph.nodes().into_iter().map(|n| n.to_string()).collect();
+        if actual_nodes != expected_nodes {
+            return Err(format!("expected nodes: {expected_nodes:?}\n  actual nodes: {actual_nodes:?}"));
+        }
+        let mut actual_edges: Vec<(String, String, i32)> = graph
+            .edges()
+            .into_iter()
+            .map(|(from, to, weight)| (from.clone(), to.clone(), weight))
+            .collect();
+        actual_edges.sort();
+        if actual_edges != expected_edges {
+            return Err(format!("expected edges: {expected_edges:?}\n  actual edges: {actual_edges:?}"));
+        }
+        Ok(())
+    }
+"#;
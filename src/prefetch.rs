@@ -0,0 +1,94 @@
+use crate::exercise::{Exercise, ExerciseOutput, Mode};
+use crate::history::content_hash;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+
+// What compiling and running an exercise ahead of time resulted in
+pub enum Prefetched {
+    // The compiler output of a failed compilation
+    CompileError(ExerciseOutput),
+    // The result of running the compiled exercise
+    Ran(Result<ExerciseOutput, ExerciseOutput>),
+}
+
+// The prefetched results by exercise file, along with the content hash of
+// the file they were compiled from
+#[derive(Default)]
+struct Cache {
+    results: HashMap<PathBuf, (String, Prefetched)>,
+    running: HashSet<PathBuf>,
+}
+
+fn cache() -> &'static (Mutex<Cache>, Condvar) {
+    static CACHE: OnceLock<(Mutex<Cache>, Condvar)> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+fn file_hash(exercise: &Exercise) -> Option<String> {
+    fs::read(&exercise.path).ok().map(|content| content_hash(&content))
+}
+
+// Compile and run the exercise in the background, so that verifying it
+// later on is instant unless it was changed in the meantime. Clippy and
+// build script exercises share their Cargo.toml with each other and are
+// only ever compiled when they are verified.
+pub fn start(exercise: &Exercise) {
+    if !matches!(exercise.mode, Mode::Compile | Mode::Test) {
+        return;
+    }
+    let hash = match file_hash(exercise) {
+        Some(hash) => hash,
+        None => return,
+    };
+    {
+        let mut cache = cache().0.lock().unwrap();
+        let cached = cache.results.get(&exercise.path).is_some_and(|(h, _)| *h == hash);
+        if cached || !cache.running.insert(exercise.path.clone()) {
+            return;
+        }
+    }
+
+    let exercise = exercise.clone();
+    thread::spawn(move || {
+        let result = match exercise.compile_now() {
+            Ok(compilation) => Prefetched::Ran(compilation.run()),
+            Err(output) => Prefetched::CompileError(output),
+        };
+        let (lock, finished) = cache();
+        let mut cache = lock.lock().unwrap();
+        cache.running.remove(&exercise.path);
+        // The result is only good for the content it was compiled from
+        if file_hash(&exercise) == Some(hash.clone()) {
+            cache.results.insert(exercise.path.clone(), (hash, result));
+        }
+        finished.notify_all();
+    });
+}
+
+// The prefetched result for the current content of the exercise, if any.
+// A compilation of it that is still running in the background is waited for.
+pub fn take(exercise: &Exercise) -> Option<Prefetched> {
+    let (lock, finished) = cache();
+    let mut cache = lock.lock().unwrap();
+    while cache.running.contains(&exercise.path) {
+        cache = finished.wait(cache).unwrap();
+    }
+    let (hash, result) = cache.results.remove(&exercise.path)?;
+    (file_hash(exercise) == Some(hash)).then_some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_prefetched_result_is_taken_once() {
+        let exercise = Exercise::for_test("compSuccess", "tests/fixture/success/compSuccess.rs", Mode::Compile);
+        start(&exercise);
+        assert!(matches!(take(&exercise), Some(Prefetched::Ran(Ok(_)))));
+        assert!(take(&exercise).is_none());
+    }
+}
@@ -0,0 +1,48 @@
+use std::path::{Component, Path, PathBuf};
+
+// The original exercise sources, embedded at build time by build.rs
+include!(concat!(env!("OUT_DIR"), "/pristine.rs"));
+
+// Where the current file is kept before an exercise is reset
+const BACKUP_DIR: &str = ".rustlings/backup";
+
+// The key of an exercise path in the pristine store:
+// relative to the rustlings directory, `/` separated and without `./`
+fn key(path: &Path) -> String {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// The original source of the exercise at the given path, if it is known
+pub fn original(path: &Path) -> Option<&'static str> {
+    let key = key(path);
+    PRISTINE_EXERCISES
+        .iter()
+        .find(|(pristine_path, _)| *pristine_path == key)
+        .map(|(_, source)| *source)
+}
+
+// The location of the backup of the exercise at the given path
+pub fn backup_path(path: &Path) -> PathBuf {
+    Path::new(BACKUP_DIR).join(key(path))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_original_ignores_current_dir() {
+        assert!(
+            !PRISTINE_EXERCISES.is_empty(),
+            "no original sources were embedded, is git available?"
+        );
+        let (path, source) = PRISTINE_EXERCISES[0];
+        assert_eq!(original(Path::new(path)), Some(source));
+        assert_eq!(original(&Path::new(".").join(path)), Some(source));
+        assert_eq!(original(Path::new("exercises/does_not_exist.rs")), None);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::exercise::Exercise;
+use crate::verify::Verification;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const PROGRESS_FILE: &str = ".rustlings/state.json";
+
+// Progress through the exercises that is not visible in the exercise files
+// themselves, kept across runs of rustlings
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct Progress {
+    // The name of the exercise watch mode is focused on
+    pub current: Option<String>,
+    // The names of the exercises that were skipped, in the order they were skipped
+    pub skipped: Vec<String>,
+    // What watch mode found when it last verified an exercise, for the
+    // dashboard to show. None while it is verifying.
+    pub verified: Option<Verified>,
+}
+
+// The outcome of verifying the exercise with the given name
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Verified {
+    pub name: String,
+    #[serde(flatten)]
+    pub verification: Verification,
+}
+
+impl Progress {
+    // Load the progress, starting over if there is none or it can't be read
+    pub fn load() -> Progress {
+        fs::read_to_string(PROGRESS_FILE)
+            .ok()
+            .and_then(|state| serde_json::from_str(&state).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Path::new(PROGRESS_FILE);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+
+    // The position of the exercise watch mode is focused on, if it still exists
+    pub fn current_index(&self, exercises: &[Exercise]) -> Option<usize> {
+        let current = self.current.as_ref()?;
+        exercises.iter().position(|e| &e.name == current)
+    }
+
+    pub fn is_skipped(&self, exercise: &Exercise) -> bool {
+        self.skipped.contains(&exercise.name)
+    }
+
+    pub fn skip(&mut self, exercise: &Exercise) {
+        if !self.is_skipped(exercise) {
+            self.skipped.push(exercise.name.clone());
+        }
+    }
+
+    pub fn unskip(&mut self, exercise: &Exercise) {
+        self.skipped.retain(|name| name != &exercise.name);
+    }
+
+    // The exercise to work on from the given position on: the first pending
+    // exercise that was not skipped, wrapping around to the start. If all
+    // pending exercises were skipped, the first of them. None if all are done.
+    pub fn next_pending(&self, exercises: &[Exercise], from: usize) -> Option<usize> {
+        let in_order = (from..exercises.len()).chain(0..from.min(exercises.len()));
+        let pending: Vec<usize> = in_order.filter(|&i| !exercises[i].looks_done()).collect();
+        pending
+            .iter()
+            .find(|&&i| !self.is_skipped(&exercises[i]))
+            .or_else(|| pending.first())
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    fn exercise(name: &str, file: &str) -> Exercise {
+        Exercise::for_test(name, format!("tests/fixture/state/{file}"), Mode::Compile)
+    }
+
+    #[test]
+    fn test_next_pending_respects_skipped() {
+        let exercises = [
+            exercise("finished", "finished_exercise.rs"),
+            exercise("pending", "pending_exercise.rs"),
+            exercise("pending_test", "pending_test_exercise.rs"),
+        ];
+        let mut progress = Progress::default();
+        assert_eq!(progress.next_pending(&exercises, 0), Some(1));
+        assert_eq!(progress.next_pending(&exercises, 2), Some(2));
+
+        progress.skip(&exercises[1]);
+        assert_eq!(progress.next_pending(&exercises, 0), Some(2));
+        progress.skip(&exercises[2]);
+        assert_eq!(progress.next_pending(&exercises, 0), Some(1));
+        progress.unskip(&exercises[1]);
+        assert_eq!(progress.skipped, vec!["pending_test".to_string()]);
+    }
+
+    #[test]
+    fn test_verified_is_kept_next_to_the_focus() {
+        // State saved before watch mode kept its verification is still read
+        let progress: Progress = serde_json::from_str(r#"{"current": "intro1", "skipped": []}"#).unwrap();
+        assert_eq!(progress.verified, None);
+
+        let progress = Progress {
+            verified: Some(Verified {
+                name: "intro1".into(),
+                verification: Verification {
+                    passed: true,
+                    output: "Hello".into(),
+                },
+            }),
+            ..progress
+        };
+        let saved = serde_json::to_value(&progress).unwrap();
+        assert_eq!(saved["verified"], serde_json::json!({ "name": "intro1", "passed": true, "output": "Hello" }));
+        assert_eq!(serde_json::from_value::<Progress>(saved).unwrap(), progress);
+    }
+}
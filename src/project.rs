@@ -1,10 +1,14 @@
-use glob::glob;
+use crate::exercise::{Exercise, Mode};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::Path;
 use std::process::Command;
 
+// The file rust-analyzer reads the exercises from
+pub const PROJECT_FILE: &str = "rust-project.json";
+
 /// Contains the structure of resulting rust-project.json file
 /// and functions to build the data required to create the file
 #[derive(Serialize, Deserialize)]
@@ -15,10 +19,14 @@ pub struct RustAnalyzerProject {
 
 #[derive(Serialize, Deserialize)]
 pub struct Crate {
+    display_name: String,
     root_module: String,
     edition: String,
     deps: Vec<String>,
     cfg: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    is_workspace_member: bool,
 }
 
 impl RustAnalyzerProject {
@@ -32,37 +40,31 @@ impl RustAnalyzerProject {
     /// Write rust-project.json to disk
     pub fn write_to_disk(&self) -> Result<(), std::io::Error> {
         std::fs::write(
-            "./rust-project.json",
+            PROJECT_FILE,
             serde_json::to_vec(&self).expect("Failed to serialize to JSON"),
         )?;
         Ok(())
     }
 
-    /// If path contains .rs extension, add a crate to `rust-project.json`
-    fn path_to_json(&mut self, path: PathBuf) -> Result<(), Box<dyn Error>> {
-        if let Some(ext) = path.extension() {
-            if ext == "rs" {
-                self.crates.push(Crate {
-                    root_module: path.display().to_string(),
-                    edition: "2021".to_string(),
-                    deps: Vec::new(),
-                    // This allows rust_analyzer to work inside #[test] blocks
-                    cfg: vec!["test".to_string()],
-                })
+    /// Add a crate to `rust-project.json` for every exercise listed in
+    /// info.toml, which allows rust-analyzer to treat it like it is compiled.
+    pub fn exercises_to_json(&mut self, exercises: &[Exercise]) {
+        for exercise in exercises.iter().filter(|e| e.path.exists()) {
+            let mut cfg = exercise.cfg.clone();
+            // This allows rust_analyzer to work inside #[test] blocks
+            if matches!(exercise.mode, Mode::Test | Mode::BuildScript) {
+                cfg.push("test".to_string());
             }
+            self.crates.push(Crate {
+                display_name: exercise.name.clone(),
+                root_module: exercise.path.display().to_string(),
+                edition: exercise.edition().to_string(),
+                deps: Vec::new(),
+                cfg,
+                env: exercise.env.clone(),
+                is_workspace_member: true,
+            });
         }
-
-        Ok(())
-    }
-
-    /// Parse the exercises folder for .rs files, any matches will create
-    /// a new `crate` in rust-project.json which allows rust-analyzer to
-    /// treat it like a normal binary
-    pub fn exercises_to_json(&mut self) -> Result<(), Box<dyn Error>> {
-        for path in glob("./exercises/**/*")? {
-            self.path_to_json(path?)?;
-        }
-        Ok(())
     }
 
     /// Use `rustc` to determine the default toolchain
@@ -84,8 +86,6 @@ impl RustAnalyzerProject {
 
         let toolchain = whitespace_iter.next().unwrap_or(&toolchain);
 
-        println!("Determined toolchain: {}\n", &toolchain);
-
         self.sysroot_src = (std::path::Path::new(&*toolchain)
             .join("lib")
             .join("rustlib")
@@ -97,3 +97,39 @@ impl RustAnalyzerProject {
         Ok(())
     }
 }
+
+// Generate rust-project.json again for the given exercises, if it was
+// generated before, so that it follows the changes to info.toml
+pub fn refresh(exercises: &[Exercise]) -> Result<bool, Box<dyn Error>> {
+    if !Path::new(PROJECT_FILE).exists() {
+        return Ok(false);
+    }
+    let mut project = RustAnalyzerProject::new();
+    project.get_sysroot_src()?;
+    project.exercises_to_json(exercises);
+    project.write_to_disk()?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::ExerciseList;
+
+    #[test]
+    fn test_crates_follow_info_toml() {
+        let exercises = ExerciseList::load(Path::new("info.toml")).unwrap().exercises;
+        let mut project = RustAnalyzerProject::new();
+        project.exercises_to_json(&exercises);
+        let find = |name: &str| project.crates.iter().find(|c| c.display_name == name).unwrap();
+
+        assert_eq!(project.crates.len(), exercises.len());
+        assert!(find("intro2").cfg.is_empty());
+        assert_eq!(find("tests1").cfg, ["test"]);
+        assert_eq!(find("tests7").env["TEST_FOO"], "0");
+        assert_eq!(find("tests8").cfg, ["feature=\"pass\"", "test"]);
+        assert!(!project.crates.iter().any(|c| c.root_module.ends_with("build.rs")));
+        // The Cargo.toml of clippy and build script exercises is no stable build file
+        assert!(!serde_json::to_string(&project).unwrap().contains("\"build\""));
+    }
+}
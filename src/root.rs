@@ -0,0 +1,61 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+const INFO_FILE: &str = "info.toml";
+const ROOT_VAR: &str = "RUSTLINGS_ROOT";
+
+// The directory of the course to work on: the one given with `--root`, the
+// one named by RUSTLINGS_ROOT, or else the closest one containing info.toml,
+// starting from the current directory and going up
+pub fn find(explicit: Option<&Path>) -> Result<PathBuf, String> {
+    if let Some(dir) = explicit {
+        return check(dir, "--root");
+    }
+    if let Some(dir) = env::var_os(ROOT_VAR) {
+        return check(Path::new(&dir), ROOT_VAR);
+    }
+
+    let current_dir = env::current_dir().map_err(|e| t!("root.no_current_dir", error = e))?;
+    search(&current_dir).ok_or_else(|| {
+        t!(
+            "root.not_found",
+            program = env::current_exe().unwrap().to_str().unwrap()
+        )
+    })
+}
+
+fn check(dir: &Path, origin: &str) -> Result<PathBuf, String> {
+    if dir.join(INFO_FILE).is_file() {
+        Ok(dir.to_path_buf())
+    } else {
+        Err(t!(
+            "root.no_info",
+            file = INFO_FILE,
+            dir = dir.display(),
+            origin = origin
+        ))
+    }
+}
+
+// The closest directory containing info.toml, from the given one upwards
+fn search(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(INFO_FILE).is_file())
+        .map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_search_goes_up_to_the_course() {
+        let repository = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert_eq!(search(&repository.join("exercises/intro")), Some(repository.to_path_buf()));
+
+        let fixture = repository.join("tests/fixture/success");
+        assert_eq!(search(&fixture), Some(fixture.clone()));
+        assert!(check(&repository.join("exercises"), "--root").is_err());
+    }
+}
@@ -1,74 +1,160 @@
-use std::process::Command;
+use std::fs;
 
-use crate::exercise::{Exercise, Mode};
-use crate::verify::test;
-use indicatif::ProgressBar;
+use crate::exercise::{Exercise, Mode, RunOptions};
+use crate::pristine;
+use crate::verify::{test, Verification};
+use crate::ui::Spinner;
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+pub fn run(exercise: &Exercise, verbose: bool, options: &RunOptions) -> Result<(), ()> {
     match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
-        Mode::BuildScript => test(exercise, verbose)?,
+        // The output of the tests is only shown when asked for
+        Mode::Test => test(
+            exercise,
+            verbose,
+            &RunOptions {
+                interactive: false,
+                ..options.clone()
+            },
+        )?,
+        Mode::Compile | Mode::Clippy => {
+            if options.test_filter.is_some() {
+                warn!("{}", t!("run.no_tests", exercise = exercise));
+            }
+            compile_and_run(exercise, options)?
+        }
+        Mode::BuildScript => {
+            if !options.args.is_empty() || options.test_filter.is_some() {
+                warn!("{}", t!("run.options_ignored", exercise = exercise));
+            }
+            test(exercise, verbose, &RunOptions::default())?
+        }
     }
     Ok(())
 }
 
-// Resets the exercise by stashing the changes.
+// Resets the exercise to its original source.
+// The current file is backed up first so that `undo-reset` can bring it back.
 pub fn reset(exercise: &Exercise) -> Result<(), ()> {
-    let command = Command::new("git")
-        .args(["stash", "--"])
-        .arg(&exercise.path)
-        .spawn();
+    match restore_original(exercise) {
+        Ok(()) => {
+            success!("{}", t!("run.reset", exercise = exercise));
+            Ok(())
+        }
+        Err(e) => {
+            warn!("{}", e);
+            Err(())
+        }
+    }
+}
+
+// Does the work of `reset` without printing anything
+pub fn restore_original(exercise: &Exercise) -> Result<(), String> {
+    let original = pristine::original(&exercise.path)
+        .ok_or_else(|| t!("run.no_original", exercise = exercise))?;
+
+    // An exercise that is already reset is not backed up again, which would
+    // replace the work kept by the reset before
+    let current = fs::read_to_string(&exercise.path).ok();
+    if exercise.path.exists() && current.as_deref() != Some(original) {
+        let backup = pristine::backup_path(&exercise.path);
+        backup
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::copy(&exercise.path, &backup))
+            .map_err(|e| t!("run.backup_failed", error = e))?;
+    }
 
-    match command {
-        Ok(_) => Ok(()),
-        Err(_) => Err(()),
+    fs::write(&exercise.path, original).map_err(|e| t!("run.reset_failed", error = e))
+}
+
+// Restores the file that was backed up by the last reset of the exercise
+pub fn undo_reset(exercise: &Exercise) -> Result<(), ()> {
+    let backup = pristine::backup_path(&exercise.path);
+    if !backup.exists() {
+        warn!("{}", t!("run.no_reset_to_undo", exercise = exercise));
+        return Err(());
+    }
+
+    if let Err(e) = fs::copy(&backup, &exercise.path).and_then(|_| fs::remove_file(&backup)) {
+        warn!("{}", t!("run.restore_failed", error = e));
+        return Err(());
     }
+    success!("{}", t!("run.restored", exercise = exercise));
+    Ok(())
 }
 
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
-// This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+// This is strictly for non-test binaries, so output is displayed. When run
+// interactively, the binary is connected to the terminal instead, so that it
+// can read from stdin.
+fn compile_and_run(exercise: &Exercise, options: &RunOptions) -> Result<(), ()> {
+    let progress_bar = Spinner::new(t!("verify.compiling", exercise = exercise));
 
     let compilation_result = exercise.compile();
     let compilation = match compilation_result {
         Ok(compilation) => compilation,
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compilation of {} failed!, Compiler error message:\n",
-                exercise
-            );
+            warn!("{}", t!("run.compile_failed", exercise = exercise));
             println!("{}", output.stderr);
             return Err(());
         }
     };
 
-    progress_bar.set_message(format!("Running {exercise}..."));
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
+    let result = if options.interactive {
+        progress_bar.finish_and_clear();
+        println!("{}", t!("verify.running", exercise = exercise));
+        compilation.run_with(options)
+    } else {
+        progress_bar.set_message(t!("verify.running", exercise = exercise));
+        let result = compilation.run_with(options);
+        progress_bar.finish_and_clear();
+        result
+    };
 
     match result {
         Ok(output) => {
             println!("{}", output.stdout);
-            success!("Successfully ran {}", exercise);
+            if !output.stderr.is_empty() {
+                println!("{}", output.stderr);
+            }
+            success!("{}", t!("run.ran", exercise = exercise));
             Ok(())
         }
         Err(output) => {
             println!("{}", output.stdout);
             println!("{}", output.stderr);
 
-            warn!("Ran {} with errors", exercise);
+            warn!("{}", t!("verify.ran_with_errors", exercise = exercise));
             Err(())
         }
     }
 }
+
+// Run the given Exercise like `run` does, collecting what would be printed
+// instead of printing it. Unlike `verify_quietly`, no reference model is
+// checked and no snapshot is taken.
+pub fn run_quietly(exercise: &Exercise) -> Verification {
+    if !exercise.path.exists() {
+        return Verification {
+            passed: false,
+            output: t!("verify.missing", exercise = exercise),
+        };
+    }
+    let (passed, output) = match exercise.compile() {
+        Ok(compilation) => match compilation.run() {
+            Ok(output) => (true, format!("{}\n{}", output.stdout, output.stderr)),
+            Err(output) => (false, format!("{}\n{}", output.stdout, output.stderr)),
+        },
+        Err(output) => (false, output.stderr),
+    };
+    Verification {
+        passed,
+        output: console::strip_ansi_codes(&output).trim().to_string(),
+    }
+}
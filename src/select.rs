@@ -0,0 +1,170 @@
+use crate::exercise::Exercise;
+use glob::Pattern;
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+// The directory rustlings was started in, relative to the root of the course
+static INVOKED_FROM: OnceLock<PathBuf> = OnceLock::new();
+
+// How many suggestions are made at most when nothing matches
+const MAX_SUGGESTIONS: usize = 3;
+
+// Remember where rustlings was started, before it changes to the root
+pub fn init(invoked_from: &Path, root: &Path) {
+    let relative = match (fs::canonicalize(invoked_from), fs::canonicalize(root)) {
+        (Ok(dir), Ok(root)) => dir.strip_prefix(root).map(Path::to_path_buf).unwrap_or_default(),
+        _ => PathBuf::new(),
+    };
+    let _ignored = INVOKED_FROM.set(relative);
+}
+
+fn invoked_from() -> &'static Path {
+    INVOKED_FROM.get().map_or(Path::new(""), PathBuf::as_path)
+}
+
+// The exercises given by the name, path or pattern, in the order of info.toml
+pub fn matching<'a>(pattern: &str, exercises: &'a [Exercise]) -> Vec<&'a Exercise> {
+    if let Some(exercise) = exercises.iter().find(|e| e.name == pattern) {
+        return vec![exercise];
+    }
+    let paths = [invoked_from().join(pattern), PathBuf::from(pattern)];
+    for path in &paths {
+        if let Some(exercise) = exercises.iter().find(|e| is_same_file(path, &e.path)) {
+            return vec![exercise];
+        }
+    }
+    if !is_glob(pattern) {
+        return Vec::new();
+    }
+    let patterns: Vec<Pattern> = paths
+        .iter()
+        .filter_map(|path| Pattern::new(&path.to_string_lossy()).ok())
+        .collect();
+    exercises
+        .iter()
+        .filter(|e| {
+            let path = e.path.strip_prefix(".").unwrap_or(&e.path);
+            patterns
+                .iter()
+                .any(|pattern| pattern.matches(&e.name) || pattern.matches_path(path))
+        })
+        .collect()
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+// The paths are compared as written first, so that an exercise whose file
+// was deleted can still be given by its path, like to reset it. Files that
+// exist are compared by the file system too, which sees through links.
+fn is_same_file(path: &Path, exercise_path: &Path) -> bool {
+    if normalize(path) == normalize(exercise_path) {
+        return true;
+    }
+    match (fs::canonicalize(path), fs::canonicalize(exercise_path)) {
+        (Ok(path), Ok(exercise_path)) => path == exercise_path,
+        _ => false,
+    }
+}
+
+// The absolute path without `.`, and with `..` taking off the directory
+// before it. Relative paths are relative to the root of the course.
+fn normalize(path: &Path) -> PathBuf {
+    let absolute = env::current_dir().unwrap_or_default().join(path);
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// The names of the exercises closest to what was given, best first, for a
+// "did you mean" when nothing matches
+pub fn suggestions<'a>(pattern: &str, exercises: &'a [Exercise]) -> Vec<&'a str> {
+    // What was given could be a path, compare it by the name of the file
+    let given = Path::new(pattern)
+        .file_stem()
+        .map_or(pattern.to_string(), |stem| stem.to_string_lossy().to_string())
+        .to_lowercase();
+    let max_distance = (given.chars().count() / 3).max(2);
+    let mut close: Vec<(usize, &str)> = exercises
+        .iter()
+        .map(|e| (distance(&given, &e.name.to_lowercase()), e.name.as_str()))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    close.sort_by_key(|(distance, _)| *distance);
+    close.into_iter().take(MAX_SUGGESTIONS).map(|(_, name)| name).collect()
+}
+
+// The number of characters to insert, delete or replace to turn one word into
+// the other, the Levenshtein distance
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let replace = previous[j] + usize::from(a != *b);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::{ExerciseList, Mode};
+
+    fn exercises() -> Vec<Exercise> {
+        ExerciseList::load(Path::new("info.toml")).unwrap().exercises
+    }
+
+    fn names<'a>(exercises: &[&'a Exercise]) -> Vec<&'a str> {
+        exercises.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_matching_names_paths_and_globs() {
+        let exercises = exercises();
+        assert_eq!(names(&matching("if1", &exercises)), ["if1"]);
+        assert_eq!(names(&matching("exercises/if/if1.rs", &exercises)), ["if1"]);
+        assert_eq!(names(&matching("./exercises/if/../if/if2.rs", &exercises)), ["if2"]);
+        assert_eq!(names(&matching("if?", &exercises)), ["if1", "if2", "if3"]);
+        assert_eq!(names(&matching("exercises/if/*.rs", &exercises)), ["if1", "if2", "if3"]);
+        assert!(matching("if9", &exercises).is_empty());
+    }
+
+    #[test]
+    fn test_matching_deleted_files() {
+        let exercises = [Exercise::for_test("gone1", "exercises/gone/gone1.rs", Mode::Compile)];
+        assert_eq!(names(&matching("exercises/gone/gone1.rs", &exercises)), ["gone1"]);
+        assert_eq!(names(&matching("./exercises/if/../gone/gone1.rs", &exercises)), ["gone1"]);
+        assert!(matching("exercises/gone/gone2.rs", &exercises).is_empty());
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let exercises = exercises();
+        assert_eq!(suggestions("iff1", &exercises)[0], "if1");
+        assert_eq!(suggestions("exercises/if/iff2.rs", &exercises)[0], "if2");
+        assert!(suggestions("completely_unrelated", &exercises).is_empty());
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("same", "same"), 0);
+    }
+}
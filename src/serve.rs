@@ -0,0 +1,208 @@
+use crate::exercise::{Exercise, ExerciseList};
+use crate::progress::Progress;
+use crate::run::{restore_original, run_quietly};
+use crate::select;
+use crate::verify::{verify_quietly, Verification};
+use crate::watch::{changed_paths, is_exercise_path, is_info_path, start_watcher};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const INFO_FILE: &str = "info.toml";
+
+// The error codes of the JSON-RPC specification, and one for requests that
+// are well-formed but can't be carried out, like one for an unknown exercise
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const REQUEST_FAILED: i64 = -32000;
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> RpcError {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+// Write a message as a single line. The lock keeps the responses and the
+// notifications of the watcher thread from interleaving.
+fn send(message: &Value) {
+    let mut stdout = io::stdout().lock();
+    let _ignored = writeln!(stdout, "{message}").and_then(|_| stdout.flush());
+}
+
+fn notify(method: &str, params: Value) {
+    send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+}
+
+// Serve JSON-RPC 2.0 requests, one per line on stdin, until stdin is
+// closed. Nothing else may be printed to stdout while serving.
+pub fn serve(exercises: Vec<Exercise>) {
+    let exercises = Arc::new(Mutex::new(exercises));
+    spawn_notifier(Arc::clone(&exercises));
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Value = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                send(&error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string())));
+                continue;
+            }
+        };
+        // Requests without an id are notifications, which get no response
+        let id = request.get("id").cloned();
+        let result = match request["method"].as_str() {
+            Some(method) => handle(method, &request["params"], &exercises.lock().unwrap()),
+            None => Err(RpcError::new(INVALID_REQUEST, "The request has no method")),
+        };
+        match (id, result) {
+            (None, _) => {}
+            (Some(id), Ok(result)) => send(&json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+            (Some(id), Err(e)) => send(&error_response(id, e)),
+        }
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn handle(method: &str, params: &Value, exercises: &[Exercise]) -> Result<Value, RpcError> {
+    match method {
+        "list" => Ok(exercises.iter().map(summary).collect()),
+        "run" => {
+            let exercise = find(params, exercises)?;
+            Ok(outcome(exercise, run_quietly(exercise)))
+        }
+        "verify" => match params.get("name") {
+            Some(_) => {
+                let exercise = find(params, exercises)?;
+                Ok(outcome(exercise, verify_quietly(exercise)))
+            }
+            None => Ok(exercises
+                .iter()
+                .map(|exercise| outcome(exercise, verify_quietly(exercise)))
+                .collect()),
+        },
+        "hint" => {
+            let exercise = find(params, exercises)?;
+            Ok(json!({ "name": exercise.name, "hint": exercise.hint.get() }))
+        }
+        "reset" => {
+            let exercise = find(params, exercises)?;
+            restore_original(exercise).map_err(|e| RpcError::new(REQUEST_FAILED, e))?;
+            Ok(summary(exercise))
+        }
+        "progress" => {
+            let progress = Progress::load();
+            let done = exercises.iter().filter(|e| e.looks_done()).count();
+            let next = progress
+                .next_pending(exercises, 0)
+                .map(|i| exercises[i].name.clone());
+            Ok(json!({
+                "done": done,
+                "total": exercises.len(),
+                "next": next,
+                "skipped": progress.skipped,
+            }))
+        }
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method {method}"))),
+    }
+}
+
+// The exercise named by the `name` parameter, which may also be its path
+fn find<'a>(params: &Value, exercises: &'a [Exercise]) -> Result<&'a Exercise, RpcError> {
+    let name = params["name"]
+        .as_str()
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "The parameter name is missing"))?;
+    match select::matching(name, exercises)[..] {
+        [exercise] => Ok(exercise),
+        [] => {
+            let mut message = t!("main.no_exercise_found", name = name);
+            let suggestions = select::suggestions(name, exercises);
+            if !suggestions.is_empty() {
+                message = format!("{message} {}", t!("main.did_you_mean", names = suggestions.join(", ")));
+            }
+            Err(RpcError::new(REQUEST_FAILED, message))
+        }
+        ref several => {
+            let names: Vec<&str> = several.iter().map(|e| e.name.as_str()).collect();
+            Err(RpcError::new(
+                REQUEST_FAILED,
+                t!("main.several_exercises", name = name, names = names.join(", ")),
+            ))
+        }
+    }
+}
+
+fn summary(exercise: &Exercise) -> Value {
+    json!({
+        "name": exercise.name,
+        "path": exercise.path,
+        "mode": exercise.mode,
+        "topic": exercise.topic(),
+        "title": exercise.title(),
+        "done": exercise.looks_done(),
+    })
+}
+
+fn outcome(exercise: &Exercise, verification: Verification) -> Value {
+    json!({
+        "name": exercise.name,
+        "passed": verification.passed,
+        "done": exercise.looks_done(),
+        "output": verification.output,
+    })
+}
+
+// Push `exerciseChanged` when the file of an exercise changes and
+// `exercisesReloaded` when info.toml does, updating the exercises served
+fn spawn_notifier(exercises: Arc<Mutex<Vec<Exercise>>>) {
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        let (_watcher, notify_error) = start_watcher(tx, None);
+        if let Some(e) = notify_error {
+            notify("warning", json!({ "message": t!("watch.polling_instead", error = e) }));
+        }
+        while let Ok(event) = rx.recv() {
+            let changed = changed_paths(event, &rx);
+            if changed.iter().any(|path| is_info_path(path)) {
+                match ExerciseList::load(Path::new(INFO_FILE)) {
+                    Ok(list) => {
+                        let names: Vec<&str> = list.exercises.iter().map(|e| e.name.as_str()).collect();
+                        notify("exercisesReloaded", json!({ "names": names }));
+                        *exercises.lock().unwrap() = list.exercises;
+                    }
+                    Err(e) => notify("warning", json!({ "message": e })),
+                }
+            }
+            let exercises = exercises.lock().unwrap();
+            for exercise in exercises.iter() {
+                if changed.iter().any(|path| is_exercise_path(path, exercise)) {
+                    notify("exerciseChanged", summary(exercise));
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,310 @@
+use crate::exercise::State;
+use crate::run::restore_original;
+use crate::verify::verify_quietly;
+use crate::watch::{
+    changed_paths, is_exercise_path, is_info_path, ExerciseStatus, Focus, ShellCommand, WatchStatus,
+};
+use notify::DebouncedEvent;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{self, IsTerminal};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+// How long to wait for a key press before checking for file changes
+const KEY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// The terminal UI needs a terminal to draw on and to read keys from
+pub fn available() -> bool {
+    io::stdout().is_terminal() && io::stdin().is_terminal()
+}
+
+// Watch mode as a full-screen terminal UI: the exercises by topic, the
+// output of the current exercise, and its hint when asked for
+pub fn watch(focus: Focus, rx: &Receiver<DebouncedEvent>) -> io::Result<WatchStatus> {
+    let mut terminal = ratatui::init();
+    let mut app = App {
+        focus,
+        output: String::new(),
+        passed: false,
+        show_hint: false,
+        scroll: 0,
+        message: None,
+    };
+    let status = app.run(&mut terminal, rx);
+    ratatui::restore();
+    status
+}
+
+// A note for the status bar, which is either good news or a problem
+enum Message {
+    Success(String),
+    Warning(String),
+}
+
+struct App {
+    focus: Focus,
+    // The output of the last verification of the current exercise
+    output: String,
+    passed: bool,
+    show_hint: bool,
+    scroll: u16,
+    message: Option<Message>,
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal, rx: &Receiver<DebouncedEvent>) -> io::Result<WatchStatus> {
+        if !self.verify(terminal, true)? {
+            return Ok(WatchStatus::Finished);
+        }
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(KEY_POLL_INTERVAL)? {
+                if let Event::Key(key) = event::read()? {
+                    match self.handle_key(terminal, key)? {
+                        Some(status) => return Ok(status),
+                        None => continue,
+                    }
+                }
+            }
+
+            while let Ok(event) = rx.try_recv() {
+                let changed = changed_paths(event, rx);
+                let reloaded = changed.iter().any(|path| is_info_path(path))
+                    && match self.focus.reload() {
+                        Ok(()) => true,
+                        Err(e) => {
+                            self.message = Some(Message::Warning(t!("watch.reload_failed", error = e)));
+                            false
+                        }
+                    };
+                if (reloaded || changed.iter().any(|path| is_exercise_path(path, self.focus.exercise())))
+                    && !self.verify(terminal, true)?
+                {
+                    return Ok(WatchStatus::Finished);
+                }
+            }
+        }
+    }
+
+    // Returns the status to end watch mode with, if it should end
+    fn handle_key(&mut self, terminal: &mut DefaultTerminal, key: KeyEvent) -> io::Result<Option<WatchStatus>> {
+        if key.kind != KeyEventKind::Press {
+            return Ok(None);
+        }
+        let navigation = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Some(WatchStatus::Unfinished)),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(Some(WatchStatus::Unfinished))
+            }
+            KeyCode::Char('r') => {
+                return self
+                    .verify(terminal, true)
+                    .map(|pending| (!pending).then_some(WatchStatus::Finished))
+            }
+            KeyCode::Char('h') => {
+                self.show_hint = !self.show_hint;
+                return Ok(None);
+            }
+            KeyCode::Char('x') => {
+                let exercise = self.focus.exercise();
+                match restore_original(exercise) {
+                    Ok(()) => {
+                        self.message = Some(Message::Success(t!(
+                            "tui.reset",
+                            exercise = exercise,
+                            name = exercise.name
+                        )))
+                    }
+                    Err(e) => self.message = Some(Message::Warning(e)),
+                }
+                return Ok(None);
+            }
+            KeyCode::Up => {
+                self.scroll = self.scroll.saturating_sub(1);
+                return Ok(None);
+            }
+            KeyCode::Down => {
+                self.scroll = self.scroll.saturating_add(1);
+                return Ok(None);
+            }
+            KeyCode::Char('n') => ShellCommand::Next,
+            KeyCode::Char('p') => ShellCommand::Prev,
+            KeyCode::Char('s') => ShellCommand::Skip,
+            _ => return Ok(None),
+        };
+        match self.focus.navigate(navigation) {
+            Ok(()) => {
+                self.verify(terminal, false)?;
+            }
+            Err(e) => self.message = Some(Message::Warning(e)),
+        }
+        Ok(None)
+    }
+
+    // Verify the current exercise, and if `advance` is set, move on to the
+    // next pending ones for as long as they are done. Returns false if all
+    // exercises are done.
+    fn verify(&mut self, terminal: &mut DefaultTerminal, advance: bool) -> io::Result<bool> {
+        loop {
+            let exercise = self.focus.exercise();
+            self.message = Some(Message::Success(t!("tui.checking", exercise = exercise)));
+            terminal.draw(|frame| self.draw(frame))?;
+
+            self.focus.publish(None);
+            let verification = verify_quietly(self.focus.exercise());
+            self.focus.publish(Some(verification.clone()));
+            self.focus.prefetch_next();
+            self.output = verification.output;
+            self.passed = verification.passed;
+            self.scroll = 0;
+            self.message = None;
+            if !self.passed {
+                return Ok(true);
+            }
+            self.message = match self.focus.passed() {
+                Some(Ok(message)) => Some(Message::Success(message)),
+                Some(Err(message)) => Some(Message::Warning(message)),
+                None => None,
+            };
+            // The file may have been deleted since it was verified
+            if !advance || !self.focus.exercise().looks_done() {
+                return Ok(true);
+            }
+            self.show_hint = false;
+            if !self.focus.advance() {
+                return Ok(false);
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [list, exercise] = Layout::horizontal([Constraint::Length(30), Constraint::Min(0)]).areas(main);
+        self.draw_list(frame, list);
+        self.draw_exercise(frame, exercise);
+
+        let status_line = match &self.message {
+            Some(Message::Success(message)) => Line::from(message.as_str()).green(),
+            Some(Message::Warning(message)) => Line::from(message.as_str()).red(),
+            None => {
+                let exercises = self.focus.exercises();
+                let done = exercises.iter().filter(|e| e.looks_done()).count();
+                Line::from(t!("tui.status", done = done, total = exercises.len())).dim()
+            }
+        };
+        frame.render_widget(status_line, status);
+    }
+
+    // The exercises grouped by topic, with the current one selected
+    fn draw_list(&self, frame: &mut Frame, area: Rect) {
+        let mut items = Vec::new();
+        let mut selected = None;
+        let mut topic = None;
+        for (i, exercise) in self.focus.exercises().iter().enumerate() {
+            let exercise_topic = exercise.topic();
+            if topic.as_ref() != Some(&exercise_topic) {
+                items.push(ListItem::new(Line::from(exercise_topic.clone()).bold()));
+                topic = Some(exercise_topic);
+            }
+            let (symbol, color) = match self.focus.status(exercise) {
+                ExerciseStatus::Done => ("✓", Color::Green),
+                ExerciseStatus::Skipped => ("»", Color::Yellow),
+                ExerciseStatus::Pending => ("·", Color::Reset),
+            };
+            if i == self.focus.current() {
+                selected = Some(items.len());
+            }
+            items.push(ListItem::new(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(symbol, Style::new().fg(color)),
+                Span::raw(format!(" {}", exercise.name)),
+            ])));
+        }
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" {} ", t!("tui.exercises"))))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(selected);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    // The output of the current exercise, with its hint below when shown
+    fn draw_exercise(&self, frame: &mut Frame, area: Rect) {
+        let exercise = self.focus.exercise();
+        let (output_area, hint_area) = if self.show_hint {
+            let [output, hint] = Layout::vertical([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(area);
+            (output, Some(hint))
+        } else {
+            (area, None)
+        };
+
+        let mut lines = Vec::new();
+        if let Some(description) = &exercise.description {
+            lines.extend(description.get().lines().map(|line| Line::from(line.to_string()).italic()));
+            lines.push(Line::default());
+        }
+        if self.passed && !exercise.path.exists() {
+            // The file was removed or renamed after it passed
+            lines.push(Line::from(t!("verify.missing", exercise = exercise)).red());
+            lines.push(Line::default());
+        } else if self.passed {
+            lines.push(Line::from(t!("tui.passes", exercise = exercise)).green().bold());
+            if exercise.state() != State::Done {
+                lines.push(Line::from(t!("tui.remove_marker")));
+            }
+            lines.push(Line::default());
+        }
+        lines.extend(self.output.lines().map(|line| Line::from(line.to_string())));
+        let title = format!(" {} ({}) ", exercise.title(), exercise.path.display());
+        let output = Paragraph::new(lines)
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(output, output_area);
+
+        if let Some(hint_area) = hint_area {
+            let hint = Paragraph::new(exercise.hint.get())
+                .block(Block::bordered().title(format!(" {} ", t!("tui.hint"))))
+                .wrap(Wrap { trim: false });
+            frame.render_widget(hint, hint_area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::{Exercise, Mode};
+    use crate::watch::WatchOptions;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_draw_passed_exercise_that_was_removed() {
+        let exercises = [Exercise::for_test("removed1", "exercises/removed/removed1.rs", Mode::Compile)];
+        let options = WatchOptions {
+            verbose: false,
+            success_hints: false,
+            auto_commit: false,
+            poll_interval: None,
+            tui: true,
+        };
+        let app = App {
+            focus: Focus::new(&exercises, &options),
+            output: String::new(),
+            passed: true,
+            show_hint: false,
+            scroll: 0,
+            message: None,
+        };
+        let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+        let frame = terminal.draw(|frame| app.draw(frame)).unwrap();
+        let text: String = frame.buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("Could not find exercises/removed/removed1.rs!"));
+    }
+}
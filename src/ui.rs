@@ -1,33 +1,192 @@
+use console::Emoji;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+pub use console::style;
+
 macro_rules! warn {
-    ($fmt:literal, $ex:expr) => {{
-        use console::{style, Emoji};
-        use std::env;
-        let formatstr = format!($fmt, $ex);
-        if env::var("NO_EMOJI").is_ok() {
-            println!("{} {}", style("!").red(), style(formatstr).red());
-        } else {
-            println!(
-                "{} {}",
-                style(Emoji("⚠️ ", "!")).red(),
-                style(formatstr).red()
-            );
-        }
-    }};
+    ($($arg:tt)*) => {
+        $crate::ui::warn(&format!($($arg)*))
+    };
 }
 
 macro_rules! success {
-    ($fmt:literal, $ex:expr) => {{
-        use console::{style, Emoji};
-        use std::env;
-        let formatstr = format!($fmt, $ex);
-        if env::var("NO_EMOJI").is_ok() {
-            println!("{} {}", style("✓").green(), style(formatstr).green());
-        } else {
+    ($($arg:tt)*) => {
+        $crate::ui::success(&format!($($arg)*))
+    };
+}
+
+// How output is presented
+struct Presentation {
+    emoji: bool,
+    // Accessible output for screen readers: no spinners, redraws or box art
+    plain: bool,
+    // Whether stdout is a terminal, rather than a file or a pipe
+    interactive: bool,
+}
+
+static PRESENTATION: OnceLock<Presentation> = OnceLock::new();
+
+// Decide how output is presented. Plain mode, NO_COLOR and NO_EMOJI take
+// precedence over the configuration, and colors are only used on a terminal
+// unless configured otherwise.
+pub fn init(plain: bool, emoji: Option<bool>, color: Option<bool>) {
+    let interactive = io::stdout().is_terminal();
+    let color = if plain || env::var_os("NO_COLOR").is_some() {
+        false
+    } else {
+        color.unwrap_or_else(|| interactive && console::colors_enabled())
+    };
+    console::set_colors_enabled(color);
+    console::set_colors_enabled_stderr(color);
+
+    let _ignored = PRESENTATION.set(Presentation {
+        emoji: env::var_os("NO_EMOJI").is_none() && emoji.unwrap_or(true) && !plain,
+        plain,
+        interactive,
+    });
+}
+
+fn presentation() -> &'static Presentation {
+    PRESENTATION.get_or_init(|| Presentation {
+        emoji: env::var_os("NO_EMOJI").is_none(),
+        plain: false,
+        interactive: io::stdout().is_terminal(),
+    })
+}
+
+pub fn emoji() -> bool {
+    presentation().emoji
+}
+
+pub fn plain() -> bool {
+    presentation().plain
+}
+
+// Whether the screen can be cleared and redrawn
+pub fn can_redraw() -> bool {
+    presentation().interactive && !presentation().plain
+}
+
+// The emoji, or its fallback when emoji are not used
+pub fn symbol<'a>(emoji: &'a str, fallback: &'a str) -> &'a str {
+    if self::emoji() {
+        emoji
+    } else {
+        fallback
+    }
+}
+
+pub fn warn(message: &str) {
+    println!(
+        "{} {}",
+        style(Emoji(symbol("⚠️ ", "!"), "!")).red(),
+        style(message).red()
+    );
+}
+
+pub fn success(message: &str) {
+    println!(
+        "{} {}",
+        style(Emoji(symbol("✅", "✓"), "✓")).green(),
+        style(message).green()
+    );
+}
+
+// Print ASCII art, which is only noise for screen readers
+pub fn art(art: &str) {
+    if !plain() {
+        println!("\n{art}\n");
+    }
+}
+
+/* Clears the terminal with an ANSI escape code.
+Works in UNIX and newer Windows terminals. */
+pub fn clear_screen() {
+    if can_redraw() {
+        println!("\x1Bc");
+    }
+}
+
+// Whether rustc should color its diagnostics, which we show as they are
+pub fn rustc_color_args() -> &'static [&'static str] {
+    if console::colors_enabled() {
+        &["--color", "always"]
+    } else {
+        &["--color", "never"]
+    }
+}
+
+// A spinner shown while waiting for something. When the screen can't be
+// redrawn, it is not shown at all, or in plain mode printed once as a line.
+pub struct Spinner(Option<ProgressBar>);
+
+impl Spinner {
+    pub fn new(message: String) -> Spinner {
+        if !can_redraw() {
+            if plain() {
+                println!("{message}");
+            }
+            return Spinner(None);
+        }
+        let progress_bar = ProgressBar::new_spinner();
+        progress_bar.set_message(message);
+        progress_bar.enable_steady_tick(100);
+        Spinner(Some(progress_bar))
+    }
+
+    pub fn set_message(&self, message: String) {
+        match &self.0 {
+            Some(progress_bar) => progress_bar.set_message(message),
+            None if plain() => println!("{message}"),
+            None => {}
+        }
+    }
+
+    pub fn finish_and_clear(&self) {
+        if let Some(progress_bar) = &self.0 {
+            progress_bar.finish_and_clear();
+        }
+    }
+}
+
+// A bar showing how many exercises are done. When the screen can't be
+// redrawn, the progress is printed once instead.
+pub struct Progress(Option<ProgressBar>);
+
+impl Progress {
+    pub fn new(done: usize, total: usize) -> Progress {
+        let percentage = done as f32 / total as f32 * 100.0;
+        if !can_redraw() {
             println!(
-                "{} {}",
-                style(Emoji("✅", "✓")).green(),
-                style(formatstr).green()
+                "{}",
+                t!(
+                    "ui.progress",
+                    done = done,
+                    total = total,
+                    percentage = format!("{percentage:.1}")
+                )
             );
+            return Progress(None);
+        }
+        let bar = ProgressBar::new(total as u64);
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template(&format!("{}: [{{bar:60.green/red}}] {{pos}}/{{len}} {{msg}}", t!("ui.progress_label")))
+                .progress_chars("#>-"),
+        );
+        bar.set_position(done as u64);
+        bar.set_message(format!("({percentage:.1} %)"));
+        Progress(Some(bar))
+    }
+
+    pub fn inc(&self) {
+        if let Some(bar) = &self.0 {
+            bar.inc(1);
+            let percentage = bar.position() as f32 / bar.length() as f32 * 100.0;
+            bar.set_message(format!("({percentage:.1} %)"));
         }
-    }};
+    }
 }
@@ -1,7 +1,8 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
-use console::style;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::env;
+use crate::exercise::{CompiledExercise, Exercise, Mode, RunOptions, State};
+use crate::history::{self, Outcome};
+use crate::model::Model;
+use crate::ui::{self, style, Progress, Spinner};
+use serde::{Deserialize, Serialize};
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
@@ -15,90 +16,171 @@ pub fn verify<'a>(
     success_hints: bool,
 ) -> Result<(), &'a Exercise> {
     let (num_done, total) = progress;
-    let bar = ProgressBar::new(total as u64);
-    let mut percentage = num_done as f32 / total as f32 * 100.0;
-    bar.set_style(ProgressStyle::default_bar()
-        .template("Progress: [{bar:60.green/red}] {pos}/{len} {msg}")
-        .progress_chars("#>-")
-    );
-    bar.set_position(num_done as u64);
-    bar.set_message(format!("({:.1} %)", percentage));
+    let bar = Progress::new(num_done, total);
 
     for exercise in exercises {
-        let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-            Mode::Compile => compile_and_run_interactively(exercise, success_hints),
-            Mode::Clippy => compile_only(exercise, success_hints),
-            Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-
-        };
-        if !compile_result.unwrap_or(false) {
+        let (done, _) = verify_exercise(exercise, verbose, success_hints);
+        if !done {
             return Err(exercise);
         }
-        percentage += 100.0 / total as f32;
-        bar.inc(1);
-        bar.set_message(format!("({:.1} %)", percentage));
+        bar.inc();
     }
     Ok(())
 }
 
+// Verify a single Exercise like `verify` does, printing the outcome. Returns
+// whether it is done, along with the outcome for showing it elsewhere too.
+pub fn verify_exercise(exercise: &Exercise, verbose: bool, success_hints: bool) -> (bool, Verification) {
+    if !exercise.path.exists() {
+        let message = t!("verify.missing", exercise = exercise);
+        warn!("{}", message);
+        return (
+            false,
+            Verification {
+                passed: false,
+                output: message,
+            },
+        );
+    }
+    let compile_result = match exercise.mode {
+        Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints, &RunOptions::default()),
+        Mode::Compile => compile_and_run_interactively(exercise, success_hints),
+        Mode::Clippy => compile_only(exercise, success_hints),
+        Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints, &RunOptions::default()),
+
+    };
+    let outcome = if compile_result.is_ok() {
+        Outcome::Passed
+    } else {
+        Outcome::Failed
+    };
+    // The history is a convenience, it must never get in the way of verifying
+    let _ignored = history::record(exercise, outcome);
+    let (done, passed, output) = match compile_result {
+        Ok((done, output)) => (done, true, output),
+        Err(output) => (false, false, output),
+    };
+    let verification = Verification {
+        passed,
+        output: console::strip_ansi_codes(&output).trim_end().to_string(),
+    };
+    (done, verification)
+}
+
+// The outcome of verifying a single exercise, for front ends that present
+// it themselves instead of having it printed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Verification {
+    // Whether the exercise compiles and its tests pass
+    pub passed: bool,
+    // The compiler diagnostics, or the output of the exercise or its tests
+    pub output: String,
+}
+
+// Verify the given Exercise like `verify` does, collecting what would be
+// printed instead of printing it
+pub fn verify_quietly(exercise: &Exercise) -> Verification {
+    if !exercise.path.exists() {
+        return Verification {
+            passed: false,
+            output: t!("verify.missing", exercise = exercise),
+        };
+    }
+    let result = match exercise.compile() {
+        Ok(compilation) => match exercise.mode {
+            Mode::Clippy => Ok(String::new()),
+            _ => match compilation.run() {
+                Ok(output) => match exercise.model {
+                    Some(model) => exercise
+                        .check_model(model)
+                        .map(|_| output.stdout)
+                        .map_err(|model_output| model_output.stderr),
+                    None => Ok(output.stdout),
+                },
+                Err(output) => Err(format!("{}\n{}", output.stdout, output.stderr)),
+            },
+        },
+        Err(output) => Err(output.stderr),
+    };
+    let outcome = if result.is_ok() {
+        Outcome::Passed
+    } else {
+        Outcome::Failed
+    };
+    let _ignored = history::record(exercise, outcome);
+    let (passed, output) = match result {
+        Ok(output) => (true, output),
+        Err(output) => (false, output),
+    };
+    Verification {
+        passed,
+        output: console::strip_ansi_codes(&output).trim_end().to_string(),
+    }
+}
+
 enum RunMode {
     Interactive,
     NonInteractive,
 }
 
 // Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    compile_and_test(exercise, RunMode::NonInteractive, verbose, false)?;
-    Ok(())
+pub fn test(exercise: &Exercise, verbose: bool, options: &RunOptions) -> Result<(), ()> {
+    compile_and_test(exercise, RunMode::NonInteractive, verbose, false, options)
+        .map(|_| ())
+        .map_err(|_| ())
 }
 
+// The steps of verifying return whether the exercise is done along with its
+// output when it passes, and the diagnostics they printed when it does not
+type StepResult = Result<(bool, String), String>;
+
 // Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+fn compile_only(exercise: &Exercise, success_hints: bool) -> StepResult {
+    let progress_bar = Spinner::new(t!("verify.compiling", exercise = exercise));
 
     let _ = compile(exercise, &progress_bar)?;
     progress_bar.finish_and_clear();
 
-    Ok(prompt_for_completion(exercise, None, success_hints))
+    Ok((prompt_for_completion(exercise, None, success_hints), String::new()))
 }
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> StepResult {
+    let progress_bar = Spinner::new(t!("verify.compiling", exercise = exercise));
 
     let compilation = compile(exercise, &progress_bar)?;
 
-    progress_bar.set_message(format!("Running {exercise}..."));
+    progress_bar.set_message(t!("verify.running", exercise = exercise));
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
     let output = match result {
         Ok(output) => output,
         Err(output) => {
-            warn!("Ran {} with errors", exercise);
+            warn!("{}", t!("verify.ran_with_errors", exercise = exercise));
             println!("{}", output.stdout);
             println!("{}", output.stderr);
-            return Err(());
+            return Err(format!("{}\n{}", output.stdout, output.stderr));
         }
     };
 
-    Ok(prompt_for_completion(exercise, Some(output.stdout), success_hints))
+    let done = prompt_for_completion(exercise, Some(output.stdout.clone()), success_hints);
+    Ok((done, output.stdout))
 }
 
 // Compile the given Exercise as a test harness and display
 // the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+fn compile_and_test(
+    exercise: &Exercise,
+    run_mode: RunMode,
+    verbose: bool,
+    success_hints: bool,
+    options: &RunOptions,
+) -> StepResult {
+    let progress_bar = Spinner::new(t!("verify.testing", exercise = exercise));
 
     let compilation = compile(exercise, &progress_bar)?;
-    let result = compilation.run();
+    let result = compilation.run_with(options);
     progress_bar.finish_and_clear();
 
     match result {
@@ -106,19 +188,38 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
             if verbose {
                 println!("{}", output.stdout);
             }
-            if let RunMode::Interactive = run_mode {
-                Ok(prompt_for_completion(exercise, None, success_hints))
-            } else {
-                Ok(true)
+            if let Some(model) = exercise.model {
+                check_model(exercise, model)?;
             }
+            let done = if let RunMode::Interactive = run_mode {
+                prompt_for_completion(exercise, None, success_hints)
+            } else {
+                true
+            };
+            Ok((done, output.stdout))
         }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            warn!("{}", t!("verify.test_failed", exercise = exercise));
             println!("{}", output.stdout);
-            Err(())
+            Err(output.stdout)
+        }
+    }
+}
+
+// Check the given Exercise against its reference model with random inputs
+// and display the minimized counterexample if they disagree
+fn check_model(exercise: &Exercise, model: Model) -> Result<(), String> {
+    let progress_bar = Spinner::new(t!("verify.checking_model", exercise = exercise));
+
+    let result = exercise.check_model(model);
+    progress_bar.finish_and_clear();
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(output) => {
+            warn!("{}", t!("verify.model_failed", exercise = exercise));
+            println!("{}", output.stderr);
+            Err(output.stderr)
         }
     }
 }
@@ -127,20 +228,17 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
 // about the state of the compilation
 fn compile<'a, 'b>(
     exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
-) -> Result<CompiledExercise<'a>, ()> {
+    progress_bar: &'b Spinner,
+) -> Result<CompiledExercise<'a>, String> {
     let compilation_result = exercise.compile();
 
     match compilation_result {
         Ok(compilation) => Ok(compilation),
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            warn!("{}", t!("verify.compile_failed", exercise = exercise));
             println!("{}", output.stderr);
-            Err(())
+            Err(output.stderr)
         }
     }
 }
@@ -151,28 +249,21 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
         State::Pending(context) => context,
     };
     match exercise.mode {
-        Mode::Compile => success!("Successfully ran {}!", exercise),
-        Mode::Test => success!("Successfully tested {}!", exercise),
-        Mode::Clippy => success!("Successfully compiled {}!", exercise),
-        Mode::BuildScript => success!("Successfully compiled {}!", exercise),
+        Mode::Compile => success!("{}", t!("verify.ran", exercise = exercise)),
+        Mode::Test => success!("{}", t!("verify.tested", exercise = exercise)),
+        Mode::Clippy => success!("{}", t!("verify.compiled", exercise = exercise)),
+        Mode::BuildScript => success!("{}", t!("verify.compiled", exercise = exercise)),
     }
 
-    let no_emoji = env::var("NO_EMOJI").is_ok();
-
-    let clippy_success_msg = if no_emoji {
-        "The code is compiling, and Clippy is happy!"
-    } else {
-        "The code is compiling, and 📎 Clippy 📎 is happy!"
-    };
-
+    let clippy = ui::symbol("📎 Clippy 📎", "Clippy");
     let success_msg = match exercise.mode {
-        Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
-        Mode::Clippy => clippy_success_msg,
-        Mode::BuildScript => "Build script works!",
+        Mode::Compile => t!("verify.compiling_done"),
+        Mode::Test => t!("verify.tests_pass"),
+        Mode::Clippy => t!("verify.clippy_happy", clippy = clippy),
+        Mode::BuildScript => t!("verify.build_script_works"),
     };
     println!();
-    if no_emoji {
+    if !ui::emoji() {
         println!("~*~ {success_msg} ~*~")
     } else {
         println!("🎉 🎉  {success_msg} 🎉 🎉")
@@ -180,24 +271,23 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
     println!();
 
     if let Some(output) = prompt_output {
-        println!("Output:");
+        println!("{}", t!("verify.output"));
         println!("{}", separator());
         println!("{output}");
         println!("{}", separator());
         println!();
     }
     if success_hints {
-        println!("Hints:");
+        println!("{}", t!("verify.hints"));
         println!("{}", separator());
         println!("{}", exercise.hint);
         println!("{}", separator());
         println!();
     }
 
-    println!("You can keep working on this exercise,");
     println!(
-        "or jump into the next one by removing the {} comment:",
-        style("`I AM NOT DONE`").bold()
+        "{}",
+        t!("verify.keep_working", marker = style("`I AM NOT DONE`").bold())
     );
     println!();
     for context_line in context {
@@ -0,0 +1,670 @@
+use crate::autocommit::commit_exercise;
+use crate::exercise::{Exercise, ExerciseList};
+use crate::history::content_hash;
+use crate::prefetch;
+use crate::progress::{Progress, Verified};
+use crate::project;
+use crate::tui;
+use crate::ui::{self, style};
+use crate::verify::{verify_exercise, Verification};
+use notify::DebouncedEvent;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// How often the main loop of watch mode checks for commands from the shell
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// How long to wait for further events before verifying a burst of them
+const EVENT_BURST_TIMEOUT: Duration = Duration::from_millis(200);
+
+// How often to check for changes when polling
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+const EXERCISES_DIR: &str = "./exercises";
+const INFO_FILE: &str = "info.toml";
+
+pub enum WatchStatus {
+    Finished,
+    Unfinished,
+}
+
+// How watch mode behaves, as given on the command line
+pub struct WatchOptions {
+    pub verbose: bool,
+    pub success_hints: bool,
+    pub auto_commit: bool,
+    // Poll for changes with this interval instead of relying on
+    // notifications from the file system
+    pub poll_interval: Option<Duration>,
+    // Show the full-screen terminal UI, if there is a terminal for it
+    pub tui: bool,
+}
+
+// Keeps whichever watcher is in use alive until it is dropped
+#[allow(dead_code)]
+pub enum ExerciseWatcher {
+    Notify(RecommendedWatcher),
+    Poll(PollWatcher),
+}
+
+// Watch the exercises and info.toml with file system notifications, falling
+// back to polling when they are not available, e.g. because the inotify limit
+// was reached or on network mounts. Returns why notifications are not used.
+pub fn start_watcher(
+    tx: Sender<DebouncedEvent>,
+    poll_interval: Option<Duration>,
+) -> (ExerciseWatcher, Option<notify::Error>) {
+    let watched = || vec![PathBuf::from(EXERCISES_DIR), PathBuf::from(INFO_FILE)];
+    if let Some(interval) = poll_interval {
+        return (ExerciseWatcher::Poll(PollWatcher::new(tx, watched(), interval)), None);
+    }
+
+    // The directory of info.toml is watched rather than the file itself,
+    // which would no longer be watched once an editor replaces it
+    let notify_watcher = RecommendedWatcher::new(tx.clone(), Duration::from_secs(1)).and_then(
+        |mut watcher: RecommendedWatcher| {
+            watcher.watch(EXERCISES_DIR, RecursiveMode::Recursive)?;
+            watcher.watch(".", RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        },
+    );
+    match notify_watcher {
+        Ok(watcher) => (ExerciseWatcher::Notify(watcher), None),
+        Err(e) => (
+            ExerciseWatcher::Poll(PollWatcher::new(tx, watched(), DEFAULT_POLL_INTERVAL)),
+            Some(e),
+        ),
+    }
+}
+
+pub fn watch(exercises: &[Exercise], options: &WatchOptions) -> WatchStatus {
+    let (tx, rx) = channel();
+    let should_quit = Arc::new(AtomicBool::new(false));
+
+    let (_watcher, notify_error) = start_watcher(tx, options.poll_interval);
+
+    ui::clear_screen();
+    if let Some(e) = notify_error {
+        warn!("{}", t!("watch.polling_instead", error = e));
+    }
+
+    let mut focus = Focus::new(exercises, options);
+    if options.tui {
+        if ui::plain() {
+            warn!("{}", t!("watch.tui_plain"));
+        } else if tui::available() {
+            return tui::watch(focus, &rx).unwrap_or_else(|e| {
+                warn!("{}", t!("watch.tui_failed", error = e));
+                WatchStatus::Unfinished
+            });
+        } else {
+            warn!("{}", t!("watch.tui_no_terminal"));
+        }
+    }
+    let failed_exercise_hint = Arc::new(Mutex::new(None));
+    if let Some(status) = focus.verify_current(&failed_exercise_hint) {
+        return status;
+    }
+    let (commands_tx, commands) = channel();
+    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit), commands_tx);
+    loop {
+        match rx.recv_timeout(COMMAND_POLL_INTERVAL) {
+            Ok(event) => {
+                let changed = changed_paths(event, &rx);
+                let reloaded = changed.iter().any(|path| is_info_path(path))
+                    && match focus.reload() {
+                        Ok(()) => true,
+                        Err(e) => {
+                            warn!("{}", t!("watch.reload_failed", error = e));
+                            false
+                        }
+                    };
+                if reloaded || changed.iter().any(|path| is_exercise_path(path, focus.exercise())) {
+                    ui::clear_screen();
+                    if reloaded {
+                        success!("{}", t!("watch.reloaded", file = INFO_FILE));
+                    }
+                    if let Some(status) = focus.verify_current(&failed_exercise_hint) {
+                        return status;
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                // the timeout expired, just check for commands and the `should_quit` variable below then loop again
+            }
+            Err(e) => println!("{}", t!("watch.error", error = format!("{e:?}"))),
+        }
+        while let Ok(command) = commands.try_recv() {
+            if let ShellCommand::List = command {
+                focus.list();
+                continue;
+            }
+            match focus.navigate(command) {
+                Ok(()) => {
+                    ui::clear_screen();
+                    focus.verify_once(&failed_exercise_hint);
+                }
+                Err(message) => println!("{message}"),
+            }
+        }
+        // Check if we need to exit
+        if should_quit.load(Ordering::SeqCst) {
+            return WatchStatus::Unfinished;
+        }
+    }
+}
+
+// Navigation and listing requested in the watch shell or with the keys of
+// the terminal UI, which are handled by the main loop of watch mode
+pub enum ShellCommand {
+    Next,
+    Prev,
+    Skip,
+    Goto(String),
+    List,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExerciseStatus {
+    Done,
+    Pending,
+    Skipped,
+}
+
+impl Display for ExerciseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            ExerciseStatus::Done => t!("list.done"),
+            ExerciseStatus::Pending => t!("list.pending"),
+            ExerciseStatus::Skipped => t!("list.skipped"),
+        }
+        .as_str())
+    }
+}
+
+// The exercise watch mode is focused on, and the progress around it
+pub struct Focus {
+    exercises: Vec<Exercise>,
+    verbose: bool,
+    success_hints: bool,
+    auto_commit: bool,
+    progress: Progress,
+    current: usize,
+    // Exercises passing when watch mode starts are not committed,
+    // only the ones that start passing while watching
+    passing: HashSet<String>,
+}
+
+impl Focus {
+    // Continue with the exercise of the last session, or the first pending one
+    pub fn new(exercises: &[Exercise], options: &WatchOptions) -> Focus {
+        let progress = Progress::load();
+        let current = progress
+            .current_index(exercises)
+            .or_else(|| progress.next_pending(exercises, 0))
+            .unwrap_or(0);
+        Focus {
+            exercises: exercises.to_vec(),
+            verbose: options.verbose,
+            success_hints: options.success_hints,
+            auto_commit: options.auto_commit,
+            progress,
+            current,
+            passing: exercises
+                .iter()
+                .filter(|e| e.looks_done())
+                .map(|e| e.name.clone())
+                .collect(),
+        }
+    }
+
+    pub fn exercise(&self) -> &Exercise {
+        &self.exercises[self.current]
+    }
+
+    pub fn exercises(&self) -> &[Exercise] {
+        &self.exercises
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn status(&self, exercise: &Exercise) -> ExerciseStatus {
+        if exercise.looks_done() {
+            ExerciseStatus::Done
+        } else if self.progress.is_skipped(exercise) {
+            ExerciseStatus::Skipped
+        } else {
+            ExerciseStatus::Pending
+        }
+    }
+
+    fn set_current(&mut self, current: usize) {
+        self.current = current;
+        self.progress.current = Some(self.exercises[current].name.clone());
+        if let Err(e) = self.progress.save() {
+            warn!("{}", t!("watch.save_failed", error = e));
+        }
+    }
+
+    // Verify the current exercise, returning whether it passes
+    fn verify_once(&mut self, failed_exercise_hint: &Mutex<Option<String>>) -> bool {
+        let exercise = self.exercise();
+        if exercise.title.is_some() || exercise.description.is_some() {
+            println!("{}", style(exercise.title()).bold());
+            if let Some(description) = &exercise.description {
+                println!("{description}");
+            }
+            println!();
+        }
+        let num_done = self.exercises.iter().filter(|e| e.looks_done()).count();
+        let bar = ui::Progress::new(num_done, self.exercises.len());
+        self.publish(None);
+        let (done, verification) = verify_exercise(self.exercise(), self.verbose, self.success_hints);
+        self.publish(Some(verification));
+        self.prefetch_next();
+        if !done {
+            *failed_exercise_hint.lock().unwrap() = Some(self.exercise().hint.to_string());
+            return false;
+        }
+        bar.inc();
+        match self.passed() {
+            Some(Ok(message)) => success!("{}", message),
+            Some(Err(message)) => warn!("{}", message),
+            None => {}
+        }
+        true
+    }
+
+    // Save the outcome of verifying the current exercise for the dashboard,
+    // or None while it is being verified
+    pub fn publish(&mut self, verification: Option<Verification>) {
+        self.progress.verified = verification.map(|verification| Verified {
+            name: self.exercise().name.clone(),
+            verification,
+        });
+        // Only the dashboard misses out when this fails
+        let _ignored = self.progress.save();
+    }
+
+    // Verify the current exercise and move on to the next pending ones for
+    // as long as they pass. Returns the status watch mode ends with, if
+    // all exercises are done.
+    fn verify_current(&mut self, failed_exercise_hint: &Mutex<Option<String>>) -> Option<WatchStatus> {
+        while self.verify_once(failed_exercise_hint) {
+            if !self.advance() {
+                return Some(WatchStatus::Finished);
+            }
+        }
+        None
+    }
+
+    // Pick up the changes to info.toml. The current exercise stays the same
+    // if it is still listed, otherwise the next pending one from where it was
+    // takes over. Progress on exercises that are no longer listed is dropped.
+    // A rust-project.json generated before is brought up to date as well.
+    pub fn reload(&mut self) -> Result<(), String> {
+        let exercises = ExerciseList::load(Path::new(INFO_FILE))?.exercises;
+        if exercises.is_empty() {
+            return Err(t!("watch.no_exercises", file = INFO_FILE));
+        }
+        let listed = |name: &String| exercises.iter().any(|e| &e.name == name);
+        self.progress.skipped.retain(|name| listed(name));
+        self.passing.retain(|name| listed(name));
+        for exercise in &exercises {
+            let is_new = !self.exercises.iter().any(|e| e.name == exercise.name);
+            if is_new && exercise.looks_done() {
+                self.passing.insert(exercise.name.clone());
+            }
+        }
+
+        let current = exercises
+            .iter()
+            .position(|e| e.name == self.exercise().name)
+            .or_else(|| self.progress.next_pending(&exercises, self.current))
+            .unwrap_or(0);
+        self.exercises = exercises;
+        self.set_current(current);
+        // rust-analyzer keeps working with the previous project if this fails
+        let _ignored = project::refresh(&self.exercises);
+        Ok(())
+    }
+
+    // Get the exercise that comes after the current one ready in the background,
+    // so that moving on to it is instant
+    pub fn prefetch_next(&self) {
+        match self.progress.next_pending(&self.exercises, self.current + 1) {
+            Some(next) if next != self.current => prefetch::start(&self.exercises[next]),
+            _ => {}
+        }
+    }
+
+    // Remember that the current exercise passes. If it just started passing,
+    // it is committed when enabled, which is what the returned message is about.
+    pub fn passed(&mut self) -> Option<Result<String, String>> {
+        let exercise = &self.exercises[self.current];
+        self.progress.unskip(exercise);
+        if !self.passing.insert(exercise.name.clone()) || !self.auto_commit {
+            return None;
+        }
+        match commit_exercise(exercise) {
+            Ok(true) => Some(Ok(t!("watch.committed", exercise = exercise))),
+            Ok(false) => None,
+            Err(e) => Some(Err(t!("watch.commit_failed", error = e))),
+        }
+    }
+
+    // Move on to the next pending exercise, returning false if there is none
+    pub fn advance(&mut self) -> bool {
+        match self.progress.next_pending(&self.exercises, self.current + 1) {
+            Some(next) => {
+                self.set_current(next);
+                true
+            }
+            None => {
+                self.progress.current = None;
+                let _ignored = self.progress.save();
+                false
+            }
+        }
+    }
+
+    // Change the current exercise as requested in the watch shell
+    pub fn navigate(&mut self, command: ShellCommand) -> Result<(), String> {
+        let exercise = &self.exercises[self.current];
+        let target = match command {
+            ShellCommand::Next => (self.current + 1..self.exercises.len())
+                .find(|&i| !self.exercises[i].looks_done() && !self.progress.is_skipped(&self.exercises[i]))
+                .ok_or_else(|| t!("watch.no_next", exercise = exercise.name))?,
+            ShellCommand::Prev => self
+                .current
+                .checked_sub(1)
+                .ok_or_else(|| t!("watch.no_prev", exercise = exercise.name))?,
+            ShellCommand::Skip => {
+                self.progress.skip(exercise);
+                match self.progress.next_pending(&self.exercises, self.current + 1) {
+                    Some(next) if next != self.current => next,
+                    _ => {
+                        self.progress.unskip(exercise);
+                        return Err(t!("watch.no_skip"));
+                    }
+                }
+            }
+            ShellCommand::Goto(name) => {
+                let target = self
+                    .exercises
+                    .iter()
+                    .position(|e| e.name == name)
+                    .ok_or_else(|| t!("main.no_exercise_found", name = name))?;
+                self.progress.unskip(&self.exercises[target]);
+                target
+            }
+            ShellCommand::List => self.current,
+        };
+        self.set_current(target);
+        Ok(())
+    }
+
+    fn list(&self) {
+        println!("  {:<17}\t{:<7}", t!("list.name"), t!("list.status"));
+        for (i, exercise) in self.exercises.iter().enumerate() {
+            let status = self.status(exercise);
+            let marker = if i == self.current { ">" } else { " " };
+            println!("{marker} {:<17}\t{status:<7}", exercise.name);
+        }
+    }
+}
+
+// A watcher for file systems that do not report changes, like network or
+// container mounts. It compares the content of every file below the watched
+// directory each interval, so that changes are noticed even when the
+// modification times are coarse or unreliable.
+pub struct PollWatcher {
+    stop: Arc<AtomicBool>,
+}
+
+impl PollWatcher {
+    fn new(tx: Sender<DebouncedEvent>, paths: Vec<PathBuf>, interval: Duration) -> PollWatcher {
+        let stop = Arc::new(AtomicBool::new(false));
+        let should_stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            let mut known = scan(&paths);
+            while !should_stop.load(Ordering::SeqCst) {
+                thread::sleep(interval);
+                let current = scan(&paths);
+                let mut events = Vec::new();
+                for (path, hash) in &current {
+                    match known.get(path) {
+                        None => events.push(DebouncedEvent::Create(path.clone())),
+                        Some(known_hash) if known_hash != hash => {
+                            events.push(DebouncedEvent::Write(path.clone()))
+                        }
+                        Some(_) => {}
+                    }
+                }
+                for path in known.keys().filter(|path| !current.contains_key(*path)) {
+                    events.push(DebouncedEvent::Remove(path.clone()));
+                }
+                if events.into_iter().any(|event| tx.send(event).is_err()) {
+                    return;
+                }
+                known = current;
+            }
+        });
+        PollWatcher { stop }
+    }
+}
+
+impl Drop for PollWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+// The content hash of every given file and of every file below the given directories
+fn scan(paths: &[PathBuf]) -> HashMap<PathBuf, String> {
+    let mut files = HashMap::new();
+    let mut dirs = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            dirs.push(path.clone());
+        } else if let Ok(content) = fs::read(path) {
+            files.insert(path.clone(), content_hash(&content));
+        }
+    }
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if path.file_name().is_some_and(|name| name != "target") {
+                    dirs.push(path);
+                }
+            } else if let Ok(content) = fs::read(&path) {
+                files.insert(path, content_hash(&content));
+            }
+        }
+    }
+    files
+}
+
+// Editors save files in many ways: writing them in place, writing a temporary
+// file and renaming it over the original, or deleting and recreating them.
+// Collect the paths touched by the given event and by all events following it
+// in quick succession, so that a burst of events is verified only once.
+pub fn changed_paths(first: DebouncedEvent, rx: &Receiver<DebouncedEvent>) -> Vec<PathBuf> {
+    let mut changed = Vec::new();
+    let mut next = Some(first);
+    while let Some(event) = next {
+        match event {
+            DebouncedEvent::Create(path)
+            | DebouncedEvent::Write(path)
+            | DebouncedEvent::Chmod(path)
+            | DebouncedEvent::Remove(path) => changed.push(path),
+            DebouncedEvent::Rename(from, to) => {
+                changed.push(from);
+                changed.push(to);
+            }
+            _ => {}
+        }
+        next = rx.recv_timeout(EVENT_BURST_TIMEOUT).ok();
+    }
+    changed
+}
+
+// Whether the path reported by the watcher is the file of the given exercise.
+// The watcher reports absolute paths while exercise paths are relative.
+pub fn is_exercise_path(path: &Path, exercise: &Exercise) -> bool {
+    is_same_file(path, &exercise.path)
+}
+
+pub fn is_info_path(path: &Path) -> bool {
+    is_same_file(path, Path::new(INFO_FILE))
+}
+
+fn is_same_file(reported: &Path, relative: &Path) -> bool {
+    let normalize = |path: &Path| -> PathBuf {
+        path.components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect()
+    };
+    normalize(reported).ends_with(normalize(relative))
+}
+
+fn spawn_watch_shell(
+    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
+    should_quit: Arc<AtomicBool>,
+    commands: Sender<ShellCommand>,
+) {
+    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
+    println!("{}", t!("shell.welcome"));
+    thread::spawn(move || loop {
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            // stdin was closed, there are no more commands to come
+            Ok(0) => break,
+            Ok(_) => {
+                let input = input.trim();
+                if input == "hint" {
+                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
+                        println!("{hint}");
+                    }
+                } else if input == "clear" {
+                    ui::clear_screen();
+                } else if input.eq("quit") {
+                    should_quit.store(true, Ordering::SeqCst);
+                    println!("{}", t!("shell.bye"));
+                } else if input.eq("help") {
+                    println!("{}", t!("shell.help"));
+                } else if let Some(cmd) = input.strip_prefix('!') {
+                    let parts: Vec<&str> = cmd.split_whitespace().collect();
+                    if parts.is_empty() {
+                        println!("{}", t!("shell.no_command"));
+                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
+                        println!("{}", t!("shell.command_failed", command = cmd, error = e));
+                    }
+                } else {
+                    let command = match input.split_whitespace().collect::<Vec<_>>()[..] {
+                        ["next"] => Some(ShellCommand::Next),
+                        ["prev"] => Some(ShellCommand::Prev),
+                        ["skip"] => Some(ShellCommand::Skip),
+                        ["list"] => Some(ShellCommand::List),
+                        ["goto", name] => Some(ShellCommand::Goto(name.to_string())),
+                        ["goto"] => {
+                            println!("{}", t!("shell.goto_usage"));
+                            continue;
+                        }
+                        _ => None,
+                    };
+                    match command {
+                        Some(command) => {
+                            if commands.send(command).is_err() {
+                                break;
+                            }
+                        }
+                        None => println!("{}", t!("shell.unknown_command", command = input)),
+                    }
+                }
+            }
+            Err(error) => println!("{}", t!("shell.read_failed", error = error)),
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    fn exercise(path: &str) -> Exercise {
+        Exercise::for_test("", path, Mode::Compile)
+    }
+
+    #[test]
+    fn test_is_exercise_path() {
+        let if1 = exercise("exercises/if/if1.rs");
+        assert!(is_exercise_path(Path::new("/home/me/rustlings/exercises/if/if1.rs"), &if1));
+        assert!(is_exercise_path(Path::new("./exercises/if/if1.rs"), &if1));
+        assert!(!is_exercise_path(Path::new("/home/me/rustlings/exercises/if/if2.rs"), &if1));
+    }
+
+    #[test]
+    fn test_is_info_path() {
+        assert!(is_info_path(Path::new("/home/me/rustlings/info.toml")));
+        assert!(is_info_path(Path::new("./info.toml")));
+        assert!(!is_info_path(Path::new("/home/me/rustlings/Cargo.toml")));
+    }
+
+    #[test]
+    fn test_changed_paths_collects_bursts() {
+        let (tx, rx): (Sender<DebouncedEvent>, _) = channel();
+        // An atomic save: the new content is written to a temporary file
+        // that is renamed over the exercise
+        tx.send(DebouncedEvent::Rename(
+            PathBuf::from("exercises/if/.if1.rs.swp"),
+            PathBuf::from("exercises/if/if1.rs"),
+        ))
+        .unwrap();
+        tx.send(DebouncedEvent::Remove(PathBuf::from("exercises/if/if2.rs"))).unwrap();
+        let first = DebouncedEvent::Write(PathBuf::from("exercises/if/if1.rs"));
+
+        let changed = changed_paths(first, &rx);
+        assert_eq!(
+            changed,
+            vec![
+                PathBuf::from("exercises/if/if1.rs"),
+                PathBuf::from("exercises/if/.if1.rs.swp"),
+                PathBuf::from("exercises/if/if1.rs"),
+                PathBuf::from("exercises/if/if2.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_poll_watcher_reports_changes() {
+        let dir = std::env::temp_dir().join(format!("rustlings-poll-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("if1.rs"), "fn main() {}\n").unwrap();
+
+        let (tx, rx) = channel();
+        let _watcher = PollWatcher::new(tx, vec![dir.clone()], Duration::from_millis(10));
+        thread::sleep(Duration::from_millis(50));
+        fs::write(dir.join("if1.rs"), "fn main() { }\n").unwrap();
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+            DebouncedEvent::Write(dir.join("if1.rs"))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
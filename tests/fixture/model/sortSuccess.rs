@@ -0,0 +1,15 @@
+fn sort<T: Ord>(array: &mut [T]) {
+    array.sort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort() {
+        let mut vec = vec![3, 1, 2];
+        sort(&mut vec);
+        assert_eq!(vec, vec![1, 2, 3]);
+    }
+}
@@ -1,8 +1,9 @@
 use assert_cmd::prelude::*;
 use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Read;
+use std::path::PathBuf;
 use std::process::Command;
 
 #[test]
@@ -15,7 +16,8 @@ fn runs_without_arguments() {
 fn fails_when_in_wrong_dir() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .current_dir("tests/")
+        .current_dir(std::env::temp_dir())
+        .env_remove("RUSTLINGS_ROOT")
         .assert()
         .code(1);
 }
@@ -40,6 +42,18 @@ fn verify_fails_if_some_fails() {
         .code(1);
 }
 
+#[test]
+fn plain_output_has_no_escape_codes() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--plain", "verify"])
+        .current_dir("tests/fixture/failure")
+        .env("CLICOLOR_FORCE", "1")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("\x1b").not());
+}
+
 #[test]
 fn run_single_compile_success() {
     Command::cargo_bin("rustlings")
@@ -126,11 +140,175 @@ fn reset_no_exercise() {
         .arg("reset")
         .assert()
         .code(1)
-        .stderr(predicates::str::contains(
-            "positional arguments not provided",
+        .stdout(predicates::str::contains(
+            "Please provide either the name of an exercise",
         ));
 }
 
+// Create a rustlings directory in a temporary location with `intro2`
+// replaced by the given source
+fn temp_rustlings_dir(test: &str, intro2: &str) -> (PathBuf, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("rustlings-{test}-{}", std::process::id()));
+    let exercise = dir.join("exercises/intro/intro2.rs");
+    fs::create_dir_all(exercise.parent().unwrap()).unwrap();
+    fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"intro2\"\npath = \"exercises/intro/intro2.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    fs::write(&exercise, intro2).unwrap();
+    (dir, exercise)
+}
+
+#[test]
+fn runs_from_subdirectory_of_course() {
+    let (dir, _) = temp_rustlings_dir("subdirectory", "fn main() {}\n");
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "intro2"])
+        .current_dir(dir.join("exercises/intro"))
+        .env_remove("RUSTLINGS_ROOT")
+        .assert()
+        .success();
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn runs_with_explicit_root() {
+    let (dir, _) = temp_rustlings_dir("root", "fn main() {}\n");
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("--root")
+        .arg(&dir)
+        .args(["run", "intro2"])
+        .current_dir(std::env::temp_dir())
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "intro2"])
+        .current_dir(std::env::temp_dir())
+        .env("RUSTLINGS_ROOT", &dir)
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--root", "exercises", "run", "intro1"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("There is no info.toml in exercises"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_layers_settings() {
+    let (dir, _) = temp_rustlings_dir("config", "fn main() {}\n");
+    let rustlings = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("rustlings").unwrap();
+        cmd.args(args).current_dir(&dir).env("XDG_CONFIG_HOME", dir.join("user"));
+        cmd
+    };
+
+    rustlings(&["config", "set", "--global", "jobs", "2"]).assert().success();
+    rustlings(&["config", "set", "jobs", "3"]).assert().success();
+    rustlings(&["config", "set", "reporter", "json"]).assert().success();
+    rustlings(&["config", "set", "jobs", "many"]).assert().code(1);
+    rustlings(&["config", "set", "colour", "false"]).assert().code(1);
+    rustlings(&["config", "get", "jobs"]).assert().success().stdout("3\n");
+    rustlings(&["config", "unset", "jobs"]).assert().success();
+    rustlings(&["config", "get", "jobs"]).assert().success().stdout("2\n");
+    rustlings(&["config", "list"])
+        .assert()
+        .success()
+        .stdout("jobs = 2 (user)\nreporter = json (project)\n");
+    rustlings(&["verify"])
+        .assert()
+        .success()
+        .stdout("{\"name\":\"intro2\",\"result\":true}\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_stops_exercise_after_timeout() {
+    let (dir, _) = temp_rustlings_dir("timeout", "fn main() {\n    loop {}\n}\n");
+    fs::write(dir.join("rustlings.toml"), "timeout = 1\n").unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "intro2"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("after it ran for 1 seconds"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reset_and_undo_reset_exercise() {
+    let (dir, exercise) = temp_rustlings_dir("reset", "// my attempt\n");
+
+    // Resetting again keeps the backup of the attempt
+    for _ in 0..2 {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["reset", "intro2"])
+            .current_dir(&dir)
+            .assert()
+            .success();
+    }
+    assert_eq!(
+        fs::read_to_string(&exercise).unwrap(),
+        include_str!("../exercises/intro/intro2.rs")
+    );
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["undo-reset", "intro2"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&exercise).unwrap(), "// my attempt\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reset_deleted_exercise_by_path() {
+    let (dir, exercise) = temp_rustlings_dir("reset-deleted", "// my attempt\n");
+    fs::remove_file(&exercise).unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "exercises/intro/intro2.rs"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&exercise).unwrap(),
+        include_str!("../exercises/intro/intro2.rs")
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reset_all_requires_confirmation() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "--all"])
+        .current_dir("tests/fixture/state")
+        .with_stdin()
+        .buffer("n\n")
+        .assert()
+        .code(1);
+}
+
 #[test]
 fn get_hint_for_single_test() {
     Command::cargo_bin("rustlings")
@@ -264,3 +442,375 @@ fn run_rustlings_list_without_done() {
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn run_single_model_check_success() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sortSuccess"])
+        .current_dir("tests/fixture/model/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_model_check_failure() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sortFailure"])
+        .env("RUSTLINGS_SEED", "42")
+        .current_dir("tests/fixture/model/")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("Minimized counterexample")
+                .and(predicates::str::contains("RUSTLINGS_SEED=42")),
+        );
+}
+
+#[test]
+fn run_stops_model_check_after_timeout() {
+    let dir = std::env::temp_dir().join(format!("rustlings-model-timeout-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"sortLoop\"\npath = \"sortLoop.rs\"\nmode = \"test\"\nmodel = \"sort\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    // Sorts what the visible test gives it, and never returns on longer inputs
+    fs::write(
+        dir.join("sortLoop.rs"),
+        "fn sort<T: Ord>(array: &mut [T]) {\n    while array.len() > 3 {}\n    array.sort();\n}\n\n#[test]\nfn test_sort() {\n    let mut vec = vec![3, 1, 2];\n    sort(&mut vec);\n    assert_eq!(vec, vec![1, 2, 3]);\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.join("rustlings.toml"), "timeout = 1\n").unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sortLoop"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("after it ran for 1 seconds"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn diff_shows_changes_against_original() {
+    let original = include_str!("../exercises/intro/intro2.rs");
+    let (dir, _) = temp_rustlings_dir("diff", &format!("{original}// my attempt\n"));
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["diff", "intro2"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("+// my attempt"));
+
+    // An exercise without a known original does not stop the others from being counted
+    let info = fs::read_to_string(dir.join("info.toml")).unwrap();
+    fs::write(
+        dir.join("info.toml"),
+        format!("[[exercises]]\nname = \"mine1\"\npath = \"exercises/mine/mine1.rs\"\nmode = \"compile\"\nhint = \"\"\n\n{info}"),
+    )
+    .unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["diff", "--stat"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("1 exercises changed, 1 insertions(+), 0 deletions(-)"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_records_history_that_can_be_restored() {
+    let original = include_str!("../exercises/intro/intro2.rs");
+    let (dir, exercise) = temp_rustlings_dir("history", original);
+    let rustlings = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("rustlings").unwrap();
+        cmd.args(args).current_dir(&dir);
+        cmd
+    };
+
+    rustlings(&["verify"]).assert().success();
+    fs::write(&exercise, "fn main() {\n").unwrap();
+    rustlings(&["verify"]).assert().code(1);
+    rustlings(&["history", "intro2"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("passed").and(predicates::str::contains("failed")));
+
+    rustlings(&["history", "intro2", "--restore", "1"]).assert().success();
+    assert_eq!(fs::read_to_string(&exercise).unwrap(), original);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn messages_follow_the_language() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "zh-CN", "run", "doesNotExist"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("找不到练习 'doesNotExist'！"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "doesNotExist"])
+        .current_dir("tests/fixture/success/")
+        .env("LANG", "zh_CN.UTF-8")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("找不到练习"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "en", "run", "doesNotExist"])
+        .current_dir("tests/fixture/success/")
+        .env("LANG", "zh_CN.UTF-8")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No exercise found for 'doesNotExist'!"));
+}
+
+#[test]
+fn hints_follow_the_language() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "zh-CN", "hint", "intro2"])
+        .assert()
+        .success()
+        .stdout("在格式字符串后面加上一个参数。\n");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "en", "hint", "intro2"])
+        .assert()
+        .success()
+        .stdout("Add an argument after the format string.\n");
+}
+
+#[test]
+fn init_translates_comments_only() {
+    let source = "// i18n: intro2.about\n// Say hello.\n\nfn main() {\n    println!(\"Hello\");\n}\n";
+    let (dir, exercise) = temp_rustlings_dir("init", source);
+    let rustlings = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("rustlings").unwrap();
+        cmd.args(args).current_dir(&dir);
+        cmd
+    };
+
+    rustlings(&["i18n", "extract"]).assert().success();
+    let catalog = dir.join("locales/exercises/zh-CN.toml");
+    assert_eq!(fs::read_to_string(&catalog).unwrap(), "\"intro2.about\" = ''\n");
+    fs::write(&catalog, "\"intro2.about\" = '打个招呼。'\n").unwrap();
+
+    rustlings(&["init", "--lang", "zh-CN"]).assert().success();
+    assert_eq!(
+        fs::read_to_string(&exercise).unwrap(),
+        "// i18n(zh-CN): intro2.about\n// 打个招呼。\n\nfn main() {\n    println!(\"Hello\");\n}\n"
+    );
+    rustlings(&["init", "--lang", "en"]).assert().success();
+    assert_eq!(fs::read_to_string(&exercise).unwrap(), source);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn editor_setup_keeps_user_tasks() {
+    let (dir, exercise) = temp_rustlings_dir("editor-setup", "fn main() {}\n");
+    let tasks = dir.join(".vscode/tasks.json");
+    fs::create_dir_all(tasks.parent().unwrap()).unwrap();
+    fs::write(&tasks, r#"{"version": "2.0.0", "tasks": [{"label": "mine"}]}"#).unwrap();
+
+    for _ in 0..2 {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["editor-setup", "vscode"])
+            .current_dir(&dir)
+            .assert()
+            .success();
+    }
+    let content = fs::read_to_string(&tasks).unwrap();
+    assert!(content.contains("\"mine\""));
+    assert_eq!(content.matches("rustlings: run current exercise").count(), 1);
+
+    // The task passes the path of the active file
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--plain", "run", exercise.to_str().unwrap()])
+        .current_dir(&dir)
+        .assert()
+        .success();
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn serve_answers_requests_on_stdio() {
+    let requests = [
+        r#"{"jsonrpc": "2.0", "id": 1, "method": "hint", "params": {"name": "finished_exercise"}}"#,
+        r#"{"jsonrpc": "2.0", "id": 2, "method": "run", "params": {"name": "pending_exercise"}}"#,
+        r#"{"jsonrpc": "2.0", "id": 3, "method": "hint", "params": {"name": "nothing"}}"#,
+        r#"{"jsonrpc": "2.0", "method": "progress"}"#,
+        r#"{"jsonrpc": "2.0", "id": 4, "method": "progress"}"#,
+    ];
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["serve", "--stdio"])
+        .current_dir("tests/fixture/state")
+        .with_stdin()
+        .buffer(requests.join("\n"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .filter(|message: &serde_json::Value| message.get("id").is_some())
+        .collect();
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[0]["result"]["name"], "finished_exercise");
+    assert_eq!(responses[1]["result"]["passed"], true);
+    assert_eq!(responses[2]["error"]["code"], -32000);
+    assert_eq!(responses[3]["id"], 4);
+    assert_eq!(responses[3]["result"]["total"], 3);
+}
+
+#[test]
+fn run_single_exercise_by_path() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "pending_exercise.rs"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success();
+}
+
+#[test]
+fn hint_for_several_exercises_by_glob() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "pending_*"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("pending_exercise").and(predicates::str::contains("pending_test_exercise")));
+}
+
+#[test]
+fn unknown_exercise_gets_suggestions() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "pendng_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Did you mean pending_exercise"));
+}
+
+#[test]
+fn run_passes_arguments_and_stdin() {
+    let source = "use std::io::BufRead;\n\nfn main() {\n    let args: Vec<String> = std::env::args().skip(1).collect();\n    println!(\"args: {}\", args.join(\" \"));\n    for line in std::io::stdin().lock().lines() {\n        println!(\"read: {}\", line.unwrap());\n    }\n}\n";
+    let (dir, _) = temp_rustlings_dir("run-args", source);
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "intro2", "--", "--flag", "value"])
+        .current_dir(&dir)
+        .with_stdin()
+        .buffer("first\nsecond\n")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("args: --flag value")
+                .and(predicates::str::contains("read: first"))
+                .and(predicates::str::contains("read: second")),
+        );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_with_timeout_reads_piped_stdin() {
+    let source = "use std::io::Read;\n\nfn main() {\n    let mut input = String::new();\n    std::io::stdin().read_to_string(&mut input).unwrap();\n    println!(\"read: {}\", input.trim());\n}\n";
+    let (dir, _) = temp_rustlings_dir("run-stdin-timeout", source);
+    fs::write(dir.join("rustlings.toml"), "timeout = 1\n").unwrap();
+
+    // The timeout applies as stdin is not a terminal, and is not reached
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "intro2"])
+        .current_dir(&dir)
+        .with_stdin()
+        .buffer("some input\n")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("read: some input"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_single_test_with_filter() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--nocapture", "run", "testSuccess", "--test", "no_such_test"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("1 filtered out").and(predicates::str::contains("SHALL PASS").not()));
+}
+
+#[test]
+fn run_shows_where_the_exercise_panicked() {
+    let source = "fn main() {\n    let v = vec![1, 2, 3];\n    println!(\"{}\", last(&v));\n}\n\nfn last(v: &[i32]) -> i32 {\n    v[5]\n}\n";
+    let (dir, _) = temp_rustlings_dir("backtrace", source);
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "intro2"])
+        .current_dir(&dir)
+        .env_remove("RUST_BACKTRACE")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("intro2::last at ./exercises/intro/intro2.rs:7:5")
+                .and(predicates::str::contains("> 7 |     v[5]"))
+                .and(predicates::str::contains("panicking.rs").not()),
+        );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn debug_prints_the_debugger_command() {
+    let (dir, _) = temp_rustlings_dir("debug", "fn main() {}\n");
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["debug", "intro2", "--print"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("rust-gdb --args .rustlings/debug/intro2"));
+    assert!(dir.join(".rustlings/debug").join(format!("intro2{}", std::env::consts::EXE_SUFFIX)).exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn watch_poll_needs_a_number_of_milliseconds() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "--poll=soon"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains("not 'soon'"));
+}